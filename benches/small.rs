@@ -0,0 +1,79 @@
+//! Compares [`SmallBMFont`] with [`BMFont`] on `assets/menu.fnt`: heap
+//! allocations while parsing and the time of a glyph lookup.
+//!
+//! Run with `cargo bench --bench small`.
+
+use bmf_parser::{BMFont, ParsedFont};
+use std::alloc::{GlobalAlloc, Layout, System};
+use std::hint::black_box;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::time::Instant;
+
+struct CountingAlloc;
+
+static ALLOCATIONS: AtomicUsize = AtomicUsize::new(0);
+static BYTES: AtomicUsize = AtomicUsize::new(0);
+
+unsafe impl GlobalAlloc for CountingAlloc {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        ALLOCATIONS.fetch_add(1, Ordering::Relaxed);
+        BYTES.fetch_add(layout.size(), Ordering::Relaxed);
+        System.alloc(layout)
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        System.dealloc(ptr, layout)
+    }
+}
+
+#[global_allocator]
+static GLOBAL: CountingAlloc = CountingAlloc;
+
+/// Allocations and bytes requested while running `f`.
+fn allocations<T>(f: impl FnOnce() -> T) -> (usize, usize) {
+    let (count, bytes) = (
+        ALLOCATIONS.load(Ordering::Relaxed),
+        BYTES.load(Ordering::Relaxed),
+    );
+    black_box(f());
+    (
+        ALLOCATIONS.load(Ordering::Relaxed) - count,
+        BYTES.load(Ordering::Relaxed) - bytes,
+    )
+}
+
+/// Average nanoseconds per call of `f` over `iterations` calls.
+fn time_per_call(iterations: u32, mut f: impl FnMut()) -> f64 {
+    let start = Instant::now();
+    for _ in 0..iterations {
+        f();
+    }
+    start.elapsed().as_nanos() as f64 / iterations as f64
+}
+
+fn main() {
+    let octets = std::fs::read("assets/menu.fnt").unwrap();
+    let bmf = BMFont::from_octets(&octets).unwrap();
+    let small = ParsedFont::from_octets(&octets).unwrap();
+    assert!(small.is_small());
+    let ids: Vec<u32> = bmf.chars.keys().copied().collect();
+
+    let (count, bytes) = allocations(|| BMFont::from_octets(&octets).unwrap());
+    println!("parse BMFont:      {count:4} allocations, {bytes:6} bytes");
+    let (count, bytes) = allocations(|| ParsedFont::from_octets(&octets).unwrap());
+    println!("parse SmallBMFont: {count:4} allocations, {bytes:6} bytes");
+
+    const ITERATIONS: u32 = 1_000;
+    let ns = time_per_call(ITERATIONS, || {
+        for &id in &ids {
+            black_box(bmf.glyph(black_box(id)));
+        }
+    });
+    println!("lookup BMFont:      {:6.2} ns", ns / ids.len() as f64);
+    let ns = time_per_call(ITERATIONS, || {
+        for &id in &ids {
+            black_box(small.glyph(black_box(id)));
+        }
+    });
+    println!("lookup SmallBMFont: {:6.2} ns", ns / ids.len() as f64);
+}
@@ -0,0 +1,145 @@
+use crate::{is_bmf, BMFont, BmfError, Char, CommonBlock, InfoBlock, KerningPair};
+use byteorder::{LittleEndian, ReadBytesExt};
+use std::collections::HashMap;
+use std::io::{self, Cursor};
+
+/// A parsed font whose strings borrow from the input buffer instead of being
+/// allocated. Convert with [`BMFontRef::to_owned`] when it must outlive it.
+#[derive(Debug, Clone)]
+pub struct BMFontRef<'a> {
+    pub info: Option<InfoBlockRef<'a>>,
+    pub common: Option<CommonBlock>,
+    pub pages: Vec<&'a str>,
+    pub chars: HashMap<u32, Char>,
+    pub kernings: Vec<KerningPair>,
+    /// Blocks of types this crate does not know, borrowed from the input;
+    /// see [`BMFont::unknown_blocks`].
+    pub unknown_blocks: Vec<(u8, &'a [u8])>,
+}
+
+/// [`InfoBlock`] with a borrowed `font_name`.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct InfoBlockRef<'a> {
+    pub font_size: i16,
+    pub bit_field: u8,
+    pub char_set: u8,
+    pub stretch_h: u16,
+    pub aa: u8,
+    pub padding: [u8; 4],
+    pub spacing: [u8; 2],
+    pub outline: u8,
+    pub font_name: &'a str,
+}
+
+impl<'a> BMFontRef<'a> {
+    pub fn from_octets(data: &'a [u8]) -> Result<Self, BmfError> {
+        if !is_bmf(data) {
+            return Err(BmfError::InvalidHeader);
+        }
+        let mut cursor = Cursor::new(data);
+        cursor.set_position(4);
+
+        let mut font = Self {
+            info: None,
+            common: None,
+            pages: Vec::new(),
+            chars: HashMap::new(),
+            kernings: Vec::new(),
+            unknown_blocks: Vec::new(),
+        };
+
+        while let Ok(block_type) = cursor.read_u8() {
+            let block_size = cursor.read_u32::<LittleEndian>()? as usize;
+            let start = cursor.position() as usize;
+            let block_data = data
+                .get(start..)
+                .and_then(|rest| rest.get(..block_size))
+                .ok_or(io::Error::from(io::ErrorKind::UnexpectedEof))?;
+            cursor.set_position((start + block_size) as u64);
+
+            match block_type {
+                1 => font.info = Some(parse_info_block(block_data)?),
+                2 => font.common = Some(BMFont::parse_common_block(block_data)?),
+                3 => font.pages = parse_pages_block(block_data)?,
+                4 => {
+                    font.chars =
+                        BMFont::parse_chars_block(block_data, crate::char_record_size(data[3]))?
+                }
+                5 => font.kernings = BMFont::parse_kerning_block(block_data)?,
+                _ => font.unknown_blocks.push((block_type, block_data)),
+            }
+        }
+
+        Ok(font)
+    }
+
+    pub fn to_owned(&self) -> BMFont {
+        BMFont {
+            info: self.info.as_ref().map(|info| InfoBlock {
+                font_size: info.font_size,
+                bit_field: info.bit_field,
+                char_set: info.char_set,
+                stretch_h: info.stretch_h,
+                aa: info.aa,
+                padding: info.padding,
+                spacing: info.spacing,
+                outline: info.outline,
+                font_name: info.font_name.to_string(),
+                #[cfg(feature = "extra-attributes")]
+                extra: Default::default(),
+            }),
+            common: self.common.clone(),
+            pages: self.pages.iter().map(|page| page.to_string()).collect(),
+            raw_pages: self.pages.iter().map(|page| page.to_string()).collect(),
+            chars: self.chars.clone(),
+            kernings: self.kernings.clone(),
+            unknown_blocks: self
+                .unknown_blocks
+                .iter()
+                .map(|(block_type, body)| (*block_type, body.to_vec()))
+                .collect(),
+            ..BMFont::new()
+        }
+    }
+}
+
+fn parse_info_block(data: &[u8]) -> io::Result<InfoBlockRef<'_>> {
+    let mut cursor = Cursor::new(data);
+    let mut info = InfoBlockRef {
+        font_size: cursor.read_i16::<LittleEndian>()?,
+        bit_field: cursor.read_u8()?,
+        char_set: cursor.read_u8()?,
+        stretch_h: cursor.read_u16::<LittleEndian>()?,
+        aa: cursor.read_u8()?,
+        padding: [
+            cursor.read_u8()?,
+            cursor.read_u8()?,
+            cursor.read_u8()?,
+            cursor.read_u8()?,
+        ],
+        spacing: [cursor.read_u8()?, cursor.read_u8()?],
+        outline: cursor.read_u8()?,
+        font_name: "",
+    };
+    let name = &data[cursor.position() as usize..];
+    let end = name.iter().position(|&b| b == 0).unwrap_or(name.len());
+    info.font_name = to_str(&name[..end])?;
+    Ok(info)
+}
+
+fn parse_pages_block(data: &[u8]) -> io::Result<Vec<&str>> {
+    let mut names: Vec<&[u8]> = data.split(|&b| b == 0).collect();
+    // A terminated last name leaves an empty piece after its null.
+    if names.last().is_some_and(|name| name.is_empty()) {
+        names.pop();
+    }
+    let mut pages = Vec::new();
+    for name in names {
+        pages.extend(crate::page_names(to_str(name)?));
+    }
+    Ok(pages)
+}
+
+fn to_str(bytes: &[u8]) -> io::Result<&str> {
+    std::str::from_utf8(bytes).map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))
+}
@@ -0,0 +1,55 @@
+use crate::{BMFont, Char};
+
+/// Which texture channels a font's glyphs are stored in, from
+/// [`BMFont::channel_usage`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ChannelUsage {
+    /// The font has no glyphs.
+    Empty,
+    /// Every glyph uses all four channels (`chnl=15`), so any channel can be
+    /// sampled directly. Exporters such as Hiero write `chnl=0` for unpacked
+    /// fonts, which counts as all channels too.
+    AllChannels,
+    /// At least one glyph is stored in a subset of the channels, so the
+    /// shader has to select the channel per glyph. `channels` is the union of
+    /// the packed glyphs' `chnl` bits (1 blue, 2 green, 4 red, 8 alpha).
+    Packed { channels: u8 },
+}
+
+impl BMFont {
+    /// Summarizes the `chnl` values of all glyphs.
+    pub fn channel_usage(&self) -> ChannelUsage {
+        if self.chars.is_empty() {
+            return ChannelUsage::Empty;
+        }
+        let channels = self
+            .chars
+            .values()
+            .filter(|ch| !matches!(ch.chnl, 0 | 15))
+            .fold(0, |mask, ch| mask | ch.chnl);
+        if channels == 0 {
+            ChannelUsage::AllChannels
+        } else {
+            ChannelUsage::Packed { channels }
+        }
+    }
+
+    /// Whether any glyph is packed into a subset of the channels, so the
+    /// shader has to select a channel per glyph instead of sampling one mask.
+    /// Stops at the first packed glyph.
+    pub fn is_multichannel(&self) -> bool {
+        self.chars.values().any(|ch| !matches!(ch.chnl, 0 | 15))
+    }
+}
+
+impl Char {
+    /// The `chnl` bits the glyph is stored in (1 blue, 2 green, 4 red,
+    /// 8 alpha), with the `chnl=0` Hiero writes for unpacked fonts reported
+    /// as all four channels.
+    pub fn channel_mask(&self) -> u8 {
+        match self.chnl {
+            0 => 15,
+            chnl => chnl,
+        }
+    }
+}
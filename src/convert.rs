@@ -0,0 +1,21 @@
+use crate::{BMFont, BmfError};
+
+/// Re-encodes a text-format font in the binary format.
+pub fn text_to_binary(text: &str) -> Result<Vec<u8>, BmfError> {
+    Ok(BMFont::from_text(text)?.to_octets())
+}
+
+/// Re-encodes a binary font in the text format.
+pub fn binary_to_text(data: &[u8]) -> Result<String, BmfError> {
+    Ok(BMFont::from_octets(data)?.to_text())
+}
+
+/// Re-encodes an XML font in the binary format.
+pub fn xml_to_binary(xml: &str) -> Result<Vec<u8>, BmfError> {
+    Ok(BMFont::from_xml(xml)?.to_octets())
+}
+
+/// Re-encodes a binary font in the XML format.
+pub fn binary_to_xml(data: &[u8]) -> Result<String, BmfError> {
+    Ok(BMFont::from_octets(data)?.to_xml())
+}
@@ -0,0 +1,70 @@
+use std::ops::RangeInclusive;
+
+/// Set of glyph ids stored as sorted, non-overlapping inclusive ranges.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct CoverageSet {
+    ranges: Vec<RangeInclusive<u32>>,
+}
+
+impl CoverageSet {
+    pub fn from_ids(ids: impl IntoIterator<Item = u32>) -> Self {
+        let mut ids: Vec<u32> = ids.into_iter().collect();
+        ids.sort_unstable();
+        ids.dedup();
+
+        let mut ranges: Vec<RangeInclusive<u32>> = Vec::new();
+        for id in ids {
+            match ranges.last_mut() {
+                Some(last) if *last.end() + 1 == id => *last = *last.start()..=id,
+                _ => ranges.push(id..=id),
+            }
+        }
+        Self { ranges }
+    }
+
+    pub fn contains(&self, id: u32) -> bool {
+        self.ranges
+            .binary_search_by(|range| {
+                if *range.end() < id {
+                    std::cmp::Ordering::Less
+                } else if *range.start() > id {
+                    std::cmp::Ordering::Greater
+                } else {
+                    std::cmp::Ordering::Equal
+                }
+            })
+            .is_ok()
+    }
+
+    pub fn ranges(&self) -> &[RangeInclusive<u32>] {
+        &self.ranges
+    }
+
+    /// Number of ids in the set.
+    pub fn len(&self) -> usize {
+        self.ranges
+            .iter()
+            .map(|range| (range.end() - range.start()) as usize + 1)
+            .sum()
+    }
+
+    /// Number of ids in the set that fall in `range`.
+    pub fn count_in(&self, range: RangeInclusive<u32>) -> usize {
+        self.ranges
+            .iter()
+            .map(|r| {
+                let start = *r.start().max(range.start());
+                let end = *r.end().min(range.end());
+                if start <= end {
+                    (end - start) as usize + 1
+                } else {
+                    0
+                }
+            })
+            .sum()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.ranges.is_empty()
+    }
+}
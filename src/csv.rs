@@ -0,0 +1,44 @@
+use crate::{BMFont, Char};
+use std::fmt::Write;
+
+impl BMFont {
+    /// Glyph metrics as CSV, one row per glyph in id order, for inspecting a
+    /// font in a spreadsheet.
+    ///
+    /// The `char` column holds the glyph's character, quoted when it is a
+    /// comma or quote, or `U+XXXX` for ids that are not printable characters.
+    pub fn to_csv(&self) -> String {
+        let mut csv =
+            String::from("id,char,x,y,width,height,x_offset,y_offset,x_advance,page,chnl\n");
+        let mut chars: Vec<&Char> = self.chars.values().collect();
+        chars.sort_by_key(|ch| ch.id);
+        for ch in chars {
+            writeln!(
+                csv,
+                "{},{},{},{},{},{},{},{},{},{},{}",
+                ch.id,
+                csv_char(ch.id),
+                ch.x,
+                ch.y,
+                ch.width,
+                ch.height,
+                ch.x_offset,
+                ch.y_offset,
+                ch.x_advance,
+                ch.page,
+                ch.chnl
+            )
+            .unwrap();
+        }
+        csv
+    }
+}
+
+fn csv_char(id: u32) -> String {
+    match char::from_u32(id) {
+        Some(c) if c.is_control() || c.is_whitespace() => format!("U+{id:04X}"),
+        Some(c @ (',' | '"')) => format!("\"{}\"", c.to_string().replace('"', "\"\"")),
+        Some(c) => c.to_string(),
+        None => format!("U+{id:04X}"),
+    }
+}
@@ -0,0 +1,213 @@
+use crate::{BMFont, Char, KerningPair};
+use std::collections::{BTreeMap, BTreeSet};
+use std::fmt;
+
+/// A single difference between two fonts, as reported by [`BMFont::diff`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum FontChange {
+    GlyphAdded(Char),
+    GlyphRemoved(Char),
+    GlyphChanged {
+        old: Char,
+        new: Char,
+    },
+    KerningAdded(KerningPair),
+    KerningRemoved(KerningPair),
+    KerningChanged {
+        first: u32,
+        second: u32,
+        old: i16,
+        new: i16,
+    },
+}
+
+/// Glyph and kerning differences between two fonts, ordered by glyph id and
+/// then kerning pair.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct FontDiff {
+    pub changes: Vec<FontChange>,
+}
+
+impl FontDiff {
+    pub fn is_empty(&self) -> bool {
+        self.changes.is_empty()
+    }
+
+    pub fn len(&self) -> usize {
+        self.changes.len()
+    }
+
+    pub fn iter(&self) -> std::slice::Iter<'_, FontChange> {
+        self.changes.iter()
+    }
+}
+
+impl<'a> IntoIterator for &'a FontDiff {
+    type Item = &'a FontChange;
+    type IntoIter = std::slice::Iter<'a, FontChange>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.changes.iter()
+    }
+}
+
+impl IntoIterator for FontDiff {
+    type Item = FontChange;
+    type IntoIter = std::vec::IntoIter<FontChange>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.changes.into_iter()
+    }
+}
+
+/// How a glyph's metrics moved between two versions of a font, from
+/// [`BMFont::metric_drift`]. Each field is the new value minus the baseline's.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct GlyphDrift {
+    pub id: u32,
+    pub x_advance: i32,
+    pub width: i32,
+    pub height: i32,
+    pub x_offset: i32,
+    pub y_offset: i32,
+}
+
+impl GlyphDrift {
+    fn between(old: &Char, new: &Char) -> Self {
+        Self {
+            id: new.id,
+            x_advance: new.x_advance as i32 - old.x_advance as i32,
+            width: new.width as i32 - old.width as i32,
+            height: new.height as i32 - old.height as i32,
+            x_offset: new.x_offset as i32 - old.x_offset as i32,
+            y_offset: new.y_offset as i32 - old.y_offset as i32,
+        }
+    }
+
+    /// The largest change of any metric, in pixels.
+    pub fn max_change(&self) -> u32 {
+        [
+            self.x_advance,
+            self.width,
+            self.height,
+            self.x_offset,
+            self.y_offset,
+        ]
+        .into_iter()
+        .map(i32::unsigned_abs)
+        .max()
+        .unwrap_or(0)
+    }
+}
+
+impl fmt::Display for FontChange {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::GlyphAdded(ch) => write!(f, "+ glyph {}", ch.id),
+            Self::GlyphRemoved(ch) => write!(f, "- glyph {}", ch.id),
+            Self::GlyphChanged { old, new } => write!(f, "~ glyph {}: {old:?} -> {new:?}", old.id),
+            Self::KerningAdded(k) => write!(f, "+ kerning {} {}: {}", k.first, k.second, k.amount),
+            Self::KerningRemoved(k) => {
+                write!(f, "- kerning {} {}: {}", k.first, k.second, k.amount)
+            }
+            Self::KerningChanged {
+                first,
+                second,
+                old,
+                new,
+            } => write!(f, "~ kerning {first} {second}: {old} -> {new}"),
+        }
+    }
+}
+
+impl fmt::Display for FontDiff {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for change in &self.changes {
+            writeln!(f, "{change}")?;
+        }
+        Ok(())
+    }
+}
+
+impl BMFont {
+    /// Glyphs and kerning pairs that differ going from `self` to `other`.
+    pub fn diff(&self, other: &BMFont) -> FontDiff {
+        let mut changes = Vec::new();
+
+        let ids: BTreeSet<u32> = self
+            .chars
+            .keys()
+            .chain(other.chars.keys())
+            .copied()
+            .collect();
+        for id in ids {
+            match (self.chars.get(&id), other.chars.get(&id)) {
+                (Some(old), None) => changes.push(FontChange::GlyphRemoved(old.clone())),
+                (None, Some(new)) => changes.push(FontChange::GlyphAdded(new.clone())),
+                (Some(old), Some(new)) if old != new => changes.push(FontChange::GlyphChanged {
+                    old: old.clone(),
+                    new: new.clone(),
+                }),
+                _ => (),
+            }
+        }
+
+        let old_kernings = kerning_map(&self.kernings);
+        let new_kernings = kerning_map(&other.kernings);
+        let pairs: BTreeSet<(u32, u32)> = old_kernings
+            .keys()
+            .chain(new_kernings.keys())
+            .copied()
+            .collect();
+        for (first, second) in pairs {
+            let pair = |amount| KerningPair {
+                first,
+                second,
+                amount,
+            };
+            match (
+                old_kernings.get(&(first, second)),
+                new_kernings.get(&(first, second)),
+            ) {
+                (Some(&old), None) => changes.push(FontChange::KerningRemoved(pair(old))),
+                (None, Some(&new)) => changes.push(FontChange::KerningAdded(pair(new))),
+                (Some(&old), Some(&new)) if old != new => {
+                    changes.push(FontChange::KerningChanged {
+                        first,
+                        second,
+                        old,
+                        new,
+                    })
+                }
+                _ => (),
+            }
+        }
+
+        FontDiff { changes }
+    }
+
+    /// Glyphs present in both fonts whose advance, size or offsets moved by
+    /// more than `threshold` pixels going from `baseline` to `self`, in id
+    /// order. A moved atlas position alone is not drift.
+    pub fn metric_drift(&self, baseline: &BMFont, threshold: u32) -> Vec<GlyphDrift> {
+        baseline
+            .diff(self)
+            .into_iter()
+            .filter_map(|change| match change {
+                FontChange::GlyphChanged { old, new } => Some(GlyphDrift::between(&old, &new)),
+                _ => None,
+            })
+            .filter(|drift| drift.max_change() > threshold)
+            .collect()
+    }
+}
+
+/// Amount of each kerning pair. Of duplicate pairs the first wins, as in
+/// [`BMFont::kerning`].
+fn kerning_map(kernings: &[KerningPair]) -> BTreeMap<(u32, u32), i16> {
+    let mut amounts = BTreeMap::new();
+    for k in kernings {
+        amounts.entry((k.first, k.second)).or_insert(k.amount);
+    }
+    amounts
+}
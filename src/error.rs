@@ -0,0 +1,101 @@
+use std::fmt;
+use std::io;
+use std::path::PathBuf;
+
+/// The blocks of a binary font, by their type byte.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum BlockKind {
+    Info = 1,
+    Common = 2,
+    Pages = 3,
+    Chars = 4,
+    Kerning = 5,
+}
+
+impl fmt::Display for BlockKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = match self {
+            Self::Info => "info",
+            Self::Common => "common",
+            Self::Pages => "pages",
+            Self::Chars => "chars",
+            Self::Kerning => "kerning",
+        };
+        f.write_str(name)
+    }
+}
+
+#[derive(Debug)]
+pub enum BmfError {
+    Io(io::Error),
+    InvalidHeader,
+    MissingPage(PathBuf),
+    BlockTooLarge {
+        block: u8,
+        size: usize,
+    },
+    /// The kerning block declares more pairs than
+    /// [`ParseOptions::max_kerning_pairs`](crate::ParseOptions::max_kerning_pairs) allows.
+    TooManyKernings {
+        count: usize,
+        max: usize,
+    },
+    /// The block header at `offset` is cut off or declares more bytes than remain.
+    BlockOutOfBounds {
+        offset: usize,
+    },
+    /// A block required by [`BMFont::from_octets_strict`](crate::BMFont::from_octets_strict)
+    /// is absent.
+    MissingBlock(BlockKind),
+    /// A text or XML font could not be parsed; `line` is 1-based.
+    Syntax {
+        line: usize,
+        message: String,
+    },
+}
+
+impl fmt::Display for BmfError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Io(e) => write!(f, "{e}"),
+            Self::InvalidHeader => write!(f, "Invalid BMFont header"),
+            Self::MissingPage(path) => write!(f, "missing page texture {}", path.display()),
+            Self::BlockTooLarge { block, size } => {
+                write!(f, "block {block} of {size} bytes exceeds the size limit")
+            }
+            Self::TooManyKernings { count, max } => {
+                write!(f, "{count} kerning pairs exceed the limit of {max}")
+            }
+            Self::BlockOutOfBounds { offset } => {
+                write!(f, "block at offset {offset} runs past the end of the data")
+            }
+            Self::MissingBlock(kind) => write!(f, "missing {kind} block"),
+            Self::Syntax { line, message } => write!(f, "line {line}: {message}"),
+        }
+    }
+}
+
+impl std::error::Error for BmfError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Self::Io(e) => Some(e),
+            _ => None,
+        }
+    }
+}
+
+impl From<io::Error> for BmfError {
+    fn from(e: io::Error) -> Self {
+        Self::Io(e)
+    }
+}
+
+impl From<BmfError> for io::Error {
+    fn from(e: BmfError) -> Self {
+        match e {
+            BmfError::Io(e) => e,
+            BmfError::MissingPage(_) => io::Error::new(io::ErrorKind::NotFound, e.to_string()),
+            _ => io::Error::new(io::ErrorKind::InvalidData, e.to_string()),
+        }
+    }
+}
@@ -0,0 +1,25 @@
+use crate::BMFont;
+use unicode_segmentation::UnicodeSegmentation;
+
+impl BMFont {
+    /// Like [`BMFont::measure`], but advancing once per extended grapheme
+    /// cluster by the glyph of its first character, so combining marks and
+    /// the parts of an emoji sequence do not add advances of their own.
+    /// Kerning applies between the first characters of neighbouring clusters.
+    pub fn measure_graphemes(&self, text: &str) -> u32 {
+        let mut width: i32 = 0;
+        let mut previous: Option<u32> = None;
+        for cluster in text.graphemes(true) {
+            let id = cluster.chars().next().unwrap() as u32;
+            let Some(ch) = self.chars.get(&id) else {
+                continue;
+            };
+            if let Some(prev) = previous {
+                width += self.kerning(prev, id) as i32;
+            }
+            width += ch.x_advance as i32;
+            previous = Some(id);
+        }
+        width.max(0) as u32
+    }
+}
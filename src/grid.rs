@@ -0,0 +1,76 @@
+use crate::{BMFont, Char, CommonBlock, InfoBlock};
+use std::collections::HashMap;
+
+impl BMFont {
+    /// Builds a synthetic monospaced font for tests and examples: the
+    /// distinct characters of `chars`, in order, fill a single page of
+    /// `cols` columns of `cell_w` by `cell_h` pixel cells, left to right and
+    /// top to bottom. Every glyph covers its whole cell, has no offset and
+    /// advances by `cell_w`; the line height and base are `cell_h`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `cols` is 0 or the atlas would not fit in `u16` pixels.
+    pub fn from_ascii_grid(chars: &str, cell_w: u16, cell_h: u16, cols: u16) -> Self {
+        assert!(cols > 0, "a glyph grid needs at least one column");
+        let mut glyphs = HashMap::new();
+        for c in chars.chars() {
+            let index = glyphs.len() as u32;
+            glyphs.entry(c as u32).or_insert_with(|| {
+                let (col, row) = (index % cols as u32, index / cols as u32);
+                Char {
+                    id: c as u32,
+                    x: pixels(col * cell_w as u32),
+                    y: pixels(row * cell_h as u32),
+                    width: cell_w,
+                    height: cell_h,
+                    x_offset: 0,
+                    y_offset: 0,
+                    x_advance: cell_w as i16,
+                    page: 0,
+                    chnl: 15,
+                    #[cfg(feature = "extra-attributes")]
+                    extra: Default::default(),
+                }
+            });
+        }
+        let rows = (glyphs.len() as u32).div_ceil(cols as u32).max(1);
+
+        BMFont {
+            info: Some(InfoBlock {
+                font_size: cell_h as i16,
+                bit_field: 0,
+                char_set: 0,
+                stretch_h: 100,
+                aa: 1,
+                padding: [0; 4],
+                spacing: [0; 2],
+                outline: 0,
+                font_name: "grid".to_string(),
+                #[cfg(feature = "extra-attributes")]
+                extra: Default::default(),
+            }),
+            common: Some(CommonBlock {
+                line_height: cell_h,
+                base: cell_h,
+                scale_w: pixels(cols as u32 * cell_w as u32),
+                scale_h: pixels(rows * cell_h as u32),
+                pages: 1,
+                bit_field: 0,
+                alpha_chnl: 0,
+                red_chnl: 0,
+                green_chnl: 0,
+                blue_chnl: 0,
+            }),
+            pages: vec!["grid.png".to_string()],
+            raw_pages: vec!["grid.png".to_string()],
+            chars: glyphs,
+            kernings: Vec::new(),
+            ..BMFont::new()
+        }
+    }
+}
+
+fn pixels(value: u32) -> u16 {
+    u16::try_from(value).expect("glyph grid does not fit in u16 pixels")
+}
@@ -0,0 +1,530 @@
+use crate::{BMFont, Char, Rect};
+use std::ops::RangeInclusive;
+
+/// A glyph placed by [`BMFont::layout`], in pixels with y pointing down.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct PositionedGlyph {
+    pub id: u32,
+    /// Top-left corner of the glyph quad.
+    pub x: f32,
+    pub y: f32,
+    pub width: f32,
+    pub height: f32,
+    /// Page the glyph is sampled from, and where on it.
+    pub page: u8,
+    pub source: Rect,
+    /// Channels the glyph is stored in, from [`Char::channel_mask`], for
+    /// shaders that select the channel per glyph of a packed font.
+    pub channels: u8,
+}
+
+/// Consecutive positioned glyphs that sample the same page.
+#[derive(Debug, Clone, PartialEq)]
+pub struct GlyphRun {
+    pub page: u8,
+    pub glyphs: Vec<PositionedGlyph>,
+}
+
+/// Options for [`BMFont::measure_with_options`] and
+/// [`BMFont::layout_with_options`].
+#[derive(Debug, Clone)]
+pub struct LayoutOptions {
+    /// Distance between tab stops in columns of the space glyph's advance.
+    /// A `\t` moves the pen to the next stop, measured from the start of the
+    /// line. 0, the default, treats tabs like any other missing glyph.
+    pub tab_width: u32,
+    /// Multiplier for `line_height` when moving to the next line. 1.0 by
+    /// default.
+    pub line_spacing: f32,
+}
+
+impl Default for LayoutOptions {
+    fn default() -> Self {
+        Self {
+            tab_width: 0,
+            line_spacing: 1.0,
+        }
+    }
+}
+
+impl BMFont {
+    /// Pen advance of a single line in pixels, including kerning.
+    /// Characters without a glyph are skipped, as in [`BMFont::layout`]: they
+    /// advance by nothing and kerning applies across them.
+    pub fn measure(&self, line: &str) -> u32 {
+        self.measure_with_options(line, &LayoutOptions::default())
+    }
+
+    /// [`BMFont::measure`] with tab stops and other [`LayoutOptions`].
+    pub fn measure_with_options(&self, line: &str, options: &LayoutOptions) -> u32 {
+        let tab_stop = self.tab_stop(options);
+        let mut width: i32 = 0;
+        let mut previous: Option<u32> = None;
+        for c in line.chars() {
+            if let (Some(stop), '\t') = (tab_stop, c) {
+                width = next_tab_stop(width as f32, stop) as i32;
+                previous = None;
+                continue;
+            }
+            let id = c as u32;
+            let Some(ch) = self.chars.get(&id) else {
+                continue;
+            };
+            if let Some(prev) = previous {
+                width += self.kerning(prev, id) as i32;
+            }
+            width += ch.x_advance as i32;
+            previous = Some(id);
+        }
+        width.max(0) as u32
+    }
+
+    /// How far the pen moves past `current` when `next` follows it: its
+    /// `x_advance` plus the kerning between the two, or just the advance when
+    /// `current` ends the text. As in [`BMFont::measure`], a missing glyph
+    /// takes no part in kerning: a missing `current` advances by 0, and a
+    /// missing `next` adds no kerning.
+    pub fn advance_to(&self, current: u32, next: Option<u32>) -> i16 {
+        let Some(ch) = self.chars.get(&current) else {
+            return 0;
+        };
+        let kerning = next
+            .filter(|next| self.chars.contains_key(next))
+            .map_or(0, |next| self.kerning(current, next));
+        ch.x_advance.saturating_add(kerning)
+    }
+
+    /// Width and height in pixels of a multi-line text block.
+    ///
+    /// The width is that of the widest line and the height is the number of
+    /// lines times `line_height`. A trailing newline starts an extra, empty
+    /// line; empty text measures `(0, 0)`.
+    pub fn measure_block(&self, text: &str) -> (u32, u32) {
+        self.measure_block_with_options(text, &LayoutOptions::default())
+    }
+
+    /// [`BMFont::measure_block`] with [`LayoutOptions`]. Each line takes
+    /// `line_height * line_spacing`, the same step
+    /// [`BMFont::layout_with_options`] moves down by, rounded once for the
+    /// whole block.
+    pub fn measure_block_with_options(&self, text: &str, options: &LayoutOptions) -> (u32, u32) {
+        if text.is_empty() {
+            return (0, 0);
+        }
+        let line_height = self.line_step(options);
+        let mut width = 0;
+        let mut line_count = 0;
+        for line in text.split('\n') {
+            let line = line.strip_suffix('\r').unwrap_or(line);
+            width = width.max(self.measure_with_options(line, options));
+            line_count += 1;
+        }
+        (
+            width,
+            (line_count as f32 * line_height).round().max(0.0) as u32,
+        )
+    }
+
+    /// `text` cut so it fits in `max_width` pixels as measured by
+    /// [`BMFont::measure`], for single-line UI labels.
+    ///
+    /// Text that already fits is returned unchanged. Otherwise the result is
+    /// the longest prefix that still fits with `ellipsis` appended, counting
+    /// the ellipsis's width and the kerning into it; empty if not even the
+    /// ellipsis on its own fits.
+    pub fn truncate_to_width(&self, text: &str, max_width: u32, ellipsis: &str) -> String {
+        if self.measure(text) <= max_width {
+            return text.to_string();
+        }
+        let ellipsis_width = self.measure(ellipsis) as i32;
+        let ellipsis_first = ellipsis
+            .chars()
+            .map(|c| c as u32)
+            .find(|id| self.chars.contains_key(id));
+        let fits = |prefix_width: i32, last: Option<u32>| {
+            let kern = match (last, ellipsis_first) {
+                (Some(last), Some(first)) => self.kerning(last, first) as i32,
+                _ => 0,
+            };
+            (prefix_width + kern + ellipsis_width).max(0) as u32 <= max_width
+        };
+
+        let mut best = fits(0, None).then_some(0);
+        let mut width: i32 = 0;
+        let mut previous: Option<u32> = None;
+        for (index, c) in text.char_indices() {
+            let id = c as u32;
+            if let Some(ch) = self.chars.get(&id) {
+                if let Some(prev) = previous {
+                    width += self.kerning(prev, id) as i32;
+                }
+                width += ch.x_advance as i32;
+                previous = Some(id);
+            }
+            if fits(width, previous) {
+                best = Some(index + c.len_utf8());
+            }
+        }
+        match best {
+            Some(end) => format!("{}{ellipsis}", &text[..end]),
+            None => String::new(),
+        }
+    }
+
+    /// `text` broken into lines no wider than `max_width` pixels as measured
+    /// by [`BMFont::measure`].
+    ///
+    /// Lines are filled greedily and broken at spaces, which are dropped at
+    /// the break; `\n` always starts a new line. A word wider than
+    /// `max_width` on its own is not split and overflows its line. Empty text
+    /// has no lines.
+    pub fn wrap<'a>(&self, text: &'a str, max_width: u32) -> Vec<&'a str> {
+        let mut lines = Vec::new();
+        if text.is_empty() {
+            return lines;
+        }
+        for paragraph in text.split('\n') {
+            let paragraph = paragraph.strip_suffix('\r').unwrap_or(paragraph);
+            let mut start = 0;
+            let mut end = None;
+            let mut word_start = 0;
+            for word in paragraph.split(' ') {
+                let word_end = word_start + word.len();
+                if !word.is_empty() {
+                    if let Some(line_end) = end {
+                        if self.measure(&paragraph[start..word_end]) > max_width {
+                            lines.push(&paragraph[start..line_end]);
+                            start = word_start;
+                        }
+                    }
+                    end = Some(word_end);
+                }
+                word_start = word_end + 1;
+            }
+            lines.push(paragraph[start..].trim_end_matches(' '));
+        }
+        lines
+    }
+
+    /// Whether `text` fits in a `width` by `height` pixel box. With `wrap` it
+    /// is broken into lines by [`BMFont::wrap`], each of which has to fit the
+    /// width, and the lines take `line_height` each; without, its lines are
+    /// only broken at `\n` and measured by [`BMFont::measure_block`].
+    pub fn fits_in(&self, text: &str, width: u32, height: u32, wrap: bool) -> bool {
+        if !wrap {
+            let (text_width, text_height) = self.measure_block(text);
+            return text_width <= width && text_height <= height;
+        }
+        let line_height = self.common.as_ref().map_or(0, |c| c.line_height as u32);
+        let lines = self.wrap(text, width);
+        lines.iter().all(|line| self.measure(line) <= width)
+            && lines.len() as u32 * line_height <= height
+    }
+
+    /// Largest `x_advance` among the digit glyphs `0`-`9`, or `None` if the
+    /// font has none of them.
+    pub fn max_digit_advance(&self) -> Option<i16> {
+        ('0'..='9')
+            .filter_map(|c| self.glyph(c))
+            .map(|ch| ch.x_advance)
+            .max()
+    }
+
+    /// Dense table of `x_advance` values for the codepoints in `range`, 0 for
+    /// those without a glyph: `table[i]` is the advance of codepoint
+    /// `range.start() + i`. Hot loops over, say, `0x20..=0x7E` can then index
+    /// instead of hashing.
+    pub fn advance_table(&self, range: RangeInclusive<u32>) -> Vec<i16> {
+        range
+            .map(|id| self.chars.get(&id).map_or(0, |ch| ch.x_advance))
+            .collect()
+    }
+
+    /// Like [`BMFont::measure`], but every digit advances by
+    /// [`BMFont::max_digit_advance`] and is not kerned, so numbers line up in
+    /// columns.
+    pub fn measure_tabular(&self, line: &str) -> u32 {
+        let digit_advance = self.max_digit_advance();
+        let mut width: i32 = 0;
+        let mut previous: Option<char> = None;
+        for c in line.chars() {
+            let tabular_advance = digit_advance.filter(|_| c.is_ascii_digit());
+            if tabular_advance.is_none() && self.glyph(c).is_none() {
+                continue;
+            }
+            if let Some(prev) = previous {
+                if tabular_advance.is_none() && !prev.is_ascii_digit() {
+                    width += self.kerning(prev as u32, c as u32) as i32;
+                }
+            }
+            if let Some(advance) = tabular_advance {
+                width += advance as i32;
+            } else if let Some(ch) = self.glyph(c) {
+                width += ch.x_advance as i32;
+            }
+            previous = Some(c);
+        }
+        width.max(0) as u32
+    }
+
+    /// Places the glyphs of `text` with the pen starting at the top of the first
+    /// line at `(start_x, start_y)`. Kerning is applied between neighbours, `\n`
+    /// starts a new line `line_height` further down, and characters without a
+    /// glyph are skipped.
+    pub fn layout(&self, text: &str, start_x: f32, start_y: f32) -> Vec<PositionedGlyph> {
+        self.layout_with_options(text, start_x, start_y, &LayoutOptions::default())
+    }
+
+    /// [`BMFont::layout`] with tab stops and other [`LayoutOptions`].
+    pub fn layout_with_options(
+        &self,
+        text: &str,
+        start_x: f32,
+        start_y: f32,
+        options: &LayoutOptions,
+    ) -> Vec<PositionedGlyph> {
+        LayoutIter::new(self, text, start_x, start_y, options).collect()
+    }
+
+    /// [`BMFont::layout`] at the origin, computed one glyph at a time so
+    /// arbitrarily long text is laid out in constant memory.
+    pub fn layout_iter<'a>(&'a self, text: &'a str) -> impl Iterator<Item = PositionedGlyph> + 'a {
+        LayoutIter::new(self, text, 0.0, 0.0, &LayoutOptions::default())
+    }
+
+    /// Places `text` in vertical columns read top to bottom, right to left,
+    /// as in CJK vertical writing, with the first column's top-left corner at
+    /// `(start_x, start_y)`.
+    ///
+    /// BMFont has no vertical metrics, so this approximates them from the
+    /// horizontal ones: columns are `line_height` wide and every glyph
+    /// advances the pen down by `line_height`, each glyph sits in its cell as
+    /// it would on a horizontal line and is centred horizontally on its
+    /// `x_advance`. `\n` starts the next column to the left. No kerning is
+    /// applied, and characters without a glyph are skipped.
+    pub fn layout_vertical(&self, text: &str, start_x: f32, start_y: f32) -> Vec<PositionedGlyph> {
+        let line_height = self.common.as_ref().map_or(0.0, |c| c.line_height as f32);
+        let mut glyphs = Vec::new();
+        let mut x = start_x;
+        let mut y = start_y;
+        for c in text.chars() {
+            if c == '\n' {
+                x -= line_height;
+                y = start_y;
+                continue;
+            }
+            let id = c as u32;
+            let Some(ch) = self.chars.get(&id) else {
+                continue;
+            };
+            let centre = (line_height - ch.x_advance as f32) / 2.0;
+            glyphs.push(PositionedGlyph {
+                id,
+                x: x + centre + ch.x_offset as f32,
+                y: y + ch.y_offset as f32,
+                width: ch.width as f32,
+                height: ch.height as f32,
+                page: ch.page,
+                source: ch.rect(),
+                channels: ch.channel_mask(),
+            });
+            y += line_height;
+        }
+        glyphs
+    }
+
+    /// [`BMFont::layout`] at the origin, grouped into runs of consecutive glyphs
+    /// on the same page so a renderer binds one atlas texture per run.
+    pub fn layout_runs(&self, text: &str) -> Vec<GlyphRun> {
+        let mut runs: Vec<GlyphRun> = Vec::new();
+        for glyph in self.layout(text, 0.0, 0.0) {
+            match runs.last_mut() {
+                Some(run) if run.page == glyph.page => run.glyphs.push(glyph),
+                _ => runs.push(GlyphRun {
+                    page: glyph.page,
+                    glyphs: vec![glyph],
+                }),
+            }
+        }
+        runs
+    }
+
+    /// Extra advance to add after each space so `line` fills `target_width`.
+    ///
+    /// Returns 0.0 when the line has no spaces or is already at least as wide
+    /// as the target.
+    pub fn justify_spacing(&self, line: &str, target_width: u32) -> f32 {
+        let natural = self.measure(line);
+        let spaces = line.chars().filter(|c| *c == ' ').count();
+        if spaces == 0 || natural >= target_width {
+            return 0.0;
+        }
+        (target_width - natural) as f32 / spaces as f32
+    }
+
+    /// `x_advance` minus the horizontal `info.spacing`.
+    ///
+    /// `spacing` is the gap BMFont leaves between glyphs in the atlas. BMFont
+    /// does not add it to `x_advance`, but some exporters do; renderers laying
+    /// out text from such a font get tight advances by using this instead.
+    /// Without an info block this is the raw `x_advance`.
+    pub fn effective_advance(&self, ch: &Char) -> i16 {
+        let spacing = self.info.as_ref().map_or(0, |info| info.spacing[0] as i16);
+        ch.x_advance.saturating_sub(spacing)
+    }
+
+    /// Distance in pixels between the tops of consecutive lines.
+    fn line_step(&self, options: &LayoutOptions) -> f32 {
+        let line_height = self.common.as_ref().map_or(0.0, |c| c.line_height as f32);
+        line_height * options.line_spacing
+    }
+
+    /// Pixel distance between tab stops, or `None` if tabs are not expanded.
+    fn tab_stop(&self, options: &LayoutOptions) -> Option<f32> {
+        let space = self.glyph(' ')?;
+        let stop = options.tab_width as f32 * space.x_advance as f32;
+        (stop > 0.0).then_some(stop)
+    }
+
+    /// Coarse ASCII preview of [`BMFont::layout`] for checking metrics in a
+    /// terminal or test output: `#` outlines each glyph quad and `-` marks the
+    /// baseline of each line. The layout is scaled down to at most 80
+    /// columns, with cells twice as tall as wide to suit terminal fonts.
+    pub fn debug_render(&self, text: &str) -> String {
+        const COLUMNS: f32 = 80.0;
+        let glyphs = self.layout(text, 0.0, 0.0);
+        let (line_height, base) = self
+            .common
+            .as_ref()
+            .map_or((0.0, 0.0), |c| (c.line_height as f32, c.base as f32));
+        let line_count = text.split('\n').count();
+        let width = glyphs.iter().map(|g| g.x + g.width).fold(1.0, f32::max);
+        let height = glyphs
+            .iter()
+            .map(|g| g.y + g.height)
+            .fold(line_count as f32 * line_height, f32::max)
+            .max(1.0);
+
+        let cell_w = (width / COLUMNS).ceil().max(1.0);
+        let cell_h = cell_w * 2.0;
+        let columns = (width / cell_w).ceil() as usize;
+        let rows = (height / cell_h).ceil() as usize;
+        let mut grid = vec![vec![' '; columns]; rows];
+        for line in 0..line_count {
+            let row = ((line as f32 * line_height + base) / cell_h) as usize;
+            if let Some(row) = grid.get_mut(row) {
+                row.fill('-');
+            }
+        }
+        for g in &glyphs {
+            let cells = |start: f32, size: f32, cell: f32| {
+                let first = (start.max(0.0) / cell) as usize;
+                let last = (((start + size) / cell).ceil() as usize).max(first + 1);
+                first..last
+            };
+            let rows = cells(g.y, g.height, cell_h);
+            let columns = cells(g.x, g.width, cell_w);
+            for row in rows.clone() {
+                for column in columns.clone() {
+                    let edge = row == rows.start
+                        || row + 1 == rows.end
+                        || column == columns.start
+                        || column + 1 == columns.end;
+                    if let Some(cell) = grid.get_mut(row).and_then(|r| r.get_mut(column)) {
+                        if edge {
+                            *cell = '#';
+                        }
+                    }
+                }
+            }
+        }
+
+        let mut out = String::new();
+        for row in grid {
+            out.extend(row);
+            let trimmed = out.trim_end_matches(' ').len();
+            out.truncate(trimmed);
+            out.push('\n');
+        }
+        out
+    }
+}
+
+/// Pen state of a layout in progress; yields the glyphs of `text` in order.
+struct LayoutIter<'a> {
+    font: &'a BMFont,
+    text: std::str::Chars<'a>,
+    tab_stop: Option<f32>,
+    line_height: f32,
+    start_x: f32,
+    x: f32,
+    y: f32,
+    previous: Option<u32>,
+}
+
+impl<'a> LayoutIter<'a> {
+    fn new(
+        font: &'a BMFont,
+        text: &'a str,
+        start_x: f32,
+        start_y: f32,
+        options: &LayoutOptions,
+    ) -> Self {
+        Self {
+            font,
+            text: text.chars(),
+            tab_stop: font.tab_stop(options),
+            line_height: font.line_step(options),
+            start_x,
+            x: start_x,
+            y: start_y,
+            previous: None,
+        }
+    }
+}
+
+impl Iterator for LayoutIter<'_> {
+    type Item = PositionedGlyph;
+
+    fn next(&mut self) -> Option<PositionedGlyph> {
+        for c in self.text.by_ref() {
+            if c == '\n' {
+                self.x = self.start_x;
+                self.y += self.line_height;
+                self.previous = None;
+                continue;
+            }
+            if let (Some(stop), '\t') = (self.tab_stop, c) {
+                self.x = self.start_x + next_tab_stop(self.x - self.start_x, stop);
+                self.previous = None;
+                continue;
+            }
+            let id = c as u32;
+            let Some(ch) = self.font.chars.get(&id) else {
+                continue;
+            };
+            if let Some(prev) = self.previous {
+                self.x += self.font.kerning(prev, id) as f32;
+            }
+            let glyph = PositionedGlyph {
+                id,
+                x: self.x + ch.x_offset as f32,
+                y: self.y + ch.y_offset as f32,
+                width: ch.width as f32,
+                height: ch.height as f32,
+                page: ch.page,
+                source: ch.rect(),
+                channels: ch.channel_mask(),
+            };
+            self.x += ch.x_advance as f32;
+            self.previous = Some(id);
+            return Some(glyph);
+        }
+        None
+    }
+}
+
+/// The first tab stop strictly after `offset` from the start of the line.
+fn next_tab_stop(offset: f32, stop: f32) -> f32 {
+    ((offset.max(0.0) / stop).floor() + 1.0) * stop
+}
@@ -1,18 +1,297 @@
+mod borrowed;
+mod channel;
+mod convert;
+mod coverage;
+mod csv;
+mod diff;
+mod error;
+#[cfg(feature = "graphemes")]
+mod grapheme;
+mod grid;
+mod hash;
+mod layout;
+mod merge;
+mod metrics;
+#[cfg(feature = "normalization")]
+mod normalize;
+mod pack;
+mod script;
+mod small;
+mod soa;
+mod stack;
+mod text;
+mod transform;
+mod validate;
+mod warning;
+mod write;
+mod xml;
+
+pub use borrowed::{BMFontRef, InfoBlockRef};
+pub use channel::ChannelUsage;
+pub use convert::{binary_to_text, binary_to_xml, text_to_binary, xml_to_binary};
+pub use coverage::CoverageSet;
+pub use diff::{FontChange, FontDiff, GlyphDrift};
+pub use error::{BlockKind, BmfError};
+pub use layout::{GlyphRun, LayoutOptions, PositionedGlyph};
+pub use merge::MergeCompatibility;
+pub use metrics::{FontMetrics, LineMetrics};
+pub use pack::PackError;
+pub use script::{CoverageLevel, UnicodeScript};
+pub use small::{ParsedFont, SmallBMFont};
+pub use soa::GlyphSoa;
+pub use stack::FontStack;
+pub use validate::ValidationIssue;
+pub use warning::ParseWarning;
+pub use write::BlockSizes;
+
 use byteorder::{LittleEndian, ReadBytesExt};
-use std::collections::HashMap;
+#[cfg(feature = "extra-attributes")]
+use std::collections::BTreeMap;
+use std::collections::{HashMap, HashSet};
+use std::fs;
 use std::io::BufRead;
 use std::io::{self, Cursor, Read};
+use std::ops::{Index, Range, RangeInclusive};
+use std::path::{Path, PathBuf};
+use std::sync::OnceLock;
+
+/// File signature at the start of every binary BMFont file.
+pub const BMF_MAGIC: [u8; 3] = [66, 77, 70];
+
+/// Binary format version this crate reads.
+pub const BMF_VERSION: u8 = 3;
+
+/// Size in bytes of one glyph record in the chars block of a version 3 file;
+/// see [`Char::from_record`].
+pub const CHAR_RECORD_SIZE: usize = 20;
+
+/// Cheap check that `data` starts with the BMFont magic and a supported version.
+pub fn is_bmf(data: &[u8]) -> bool {
+    data.len() >= 4 && data[..3] == BMF_MAGIC && data[3] == BMF_VERSION
+}
+
+/// Checks that `data` is a BMFont file whose blocks exactly fill it, without
+/// decoding any block contents.
+pub fn validate_structure(data: &[u8]) -> Result<(), BmfError> {
+    if !is_bmf(data) {
+        return Err(BmfError::InvalidHeader);
+    }
+    let mut offset = 4;
+    while offset < data.len() {
+        let size = data
+            .get(offset + 1..offset + 5)
+            .map(|size| u32::from_le_bytes(size.try_into().unwrap()) as usize)
+            .ok_or(BmfError::BlockOutOfBounds { offset })?;
+        let end = (offset + 5)
+            .checked_add(size)
+            .filter(|end| *end <= data.len())
+            .ok_or(BmfError::BlockOutOfBounds { offset })?;
+        offset = end;
+    }
+    Ok(())
+}
+
+/// The chars block of a binary font as raw bytes, a run of 20-byte records
+/// in file order, for callers that decode glyphs into their own layout
+/// instead of [`BMFont::chars`]. When a file has several chars blocks this
+/// is the last one, the block [`BMFont::from_octets`] keeps. `None` if
+/// `data` is not a BMFont file, has no chars block, or a block is cut off.
+pub fn chars_raw(data: &[u8]) -> Option<&[u8]> {
+    if !is_bmf(data) {
+        return None;
+    }
+    let mut chars = None;
+    let mut offset = 4;
+    while offset < data.len() {
+        let block_type = data[offset];
+        let size = u32::from_le_bytes(data.get(offset + 1..offset + 5)?.try_into().unwrap());
+        let start = offset + 5;
+        let end = start
+            .checked_add(size as usize)
+            .filter(|end| *end <= data.len())?;
+        if block_type == 4 {
+            chars = Some(&data[start..end]);
+        }
+        offset = end;
+    }
+    chars
+}
+
+/// Every record of the chars block in file order, duplicates included, for
+/// diagnosing fonts whose [`BMFont::chars`] map silently kept only the last
+/// glyph of each id. A font without a chars block gives an empty list.
+pub fn parse_chars_list(data: &[u8]) -> Result<Vec<Char>, BmfError> {
+    validate_structure(data)?;
+    let record_size = char_record_size(data[3]);
+    let records = chars_raw(data).unwrap_or_default();
+    records
+        .chunks(record_size)
+        .map(|record| {
+            if record.len() < record_size {
+                return Err(io::Error::from(io::ErrorKind::UnexpectedEof).into());
+            }
+            Ok(BMFont::parse_char_record(record)?)
+        })
+        .collect()
+}
+
+/// Ids that occur more than once in `chars`, such as a list from
+/// [`parse_chars_list`], sorted.
+pub fn duplicate_ids(chars: &[Char]) -> Vec<u32> {
+    let mut ids: Vec<u32> = chars.iter().map(|ch| ch.id).collect();
+    ids.sort_unstable();
+    let mut duplicates: Vec<u32> = ids
+        .windows(2)
+        .filter(|w| w[0] == w[1])
+        .map(|w| w[0])
+        .collect();
+    duplicates.dedup();
+    duplicates
+}
+
+/// Every distinct codepoint used in `strings`, such as an application's
+/// string table, ready to pass to [`BMFont::subset`].
+pub fn required_glyphs(strings: impl IntoIterator<Item = impl AsRef<str>>) -> HashSet<u32> {
+    let mut ids = HashSet::new();
+    for s in strings {
+        ids.extend(s.as_ref().chars().map(|c| c as u32));
+    }
+    ids
+}
+
+/// How often each pair of adjacent characters occurs in `text`, for finding
+/// the frequent pairs a font's kerning table lacks. Pairs across a line break
+/// are not counted, as layout does not kern them.
+pub fn pair_frequencies(text: &str) -> HashMap<(char, char), u32> {
+    let mut counts = HashMap::new();
+    for line in text.lines() {
+        let chars: Vec<char> = line.chars().collect();
+        for pair in chars.windows(2) {
+            *counts.entry((pair[0], pair[1])).or_insert(0) += 1;
+        }
+    }
+    counts
+}
+
+/// Decodes a name as UTF-8. With `lossy`, invalid sequences become U+FFFD
+/// instead of an `InvalidData` error.
+fn decode_name(bytes: Vec<u8>, lossy: bool) -> io::Result<String> {
+    match String::from_utf8(bytes) {
+        Ok(name) => Ok(name),
+        Err(e) if lossy => Ok(String::from_utf8_lossy(e.as_bytes()).into_owned()),
+        Err(e) => Err(io::Error::new(io::ErrorKind::InvalidData, e)),
+    }
+}
+
+/// Size in bytes of one record in the chars block for a given format version.
+/// A version with a larger record only needs a new arm here.
+const fn char_record_size(version: u8) -> usize {
+    match version {
+        3 => CHAR_RECORD_SIZE,
+        // Other versions are rejected by `is_bmf` before any record is read.
+        _ => CHAR_RECORD_SIZE,
+    }
+}
+
+const KERNING_RECORD_SIZE: usize = 10;
+
+/// Container the block parser reads the chars block into.
+pub(crate) trait GlyphTable: Default {
+    /// The glyphs of a run of char records. A later record replaces an
+    /// earlier one with the same id.
+    fn from_records(records: &[u8], record_size: usize) -> io::Result<Self>;
+
+    /// Number of distinct glyphs.
+    fn len(&self) -> usize;
+}
 
-#[derive(Debug)]
+impl GlyphTable for HashMap<u32, Char> {
+    fn from_records(records: &[u8], record_size: usize) -> io::Result<Self> {
+        BMFont::parse_chars_block(records, record_size)
+    }
+
+    fn len(&self) -> usize {
+        HashMap::len(self)
+    }
+}
+
+/// Feeds extra attributes into a content hash. Nothing is written when there
+/// are none, so fonts without them hash the same with or without the feature.
+#[cfg(feature = "extra-attributes")]
+fn hash_extra(hasher: &mut hash::Fnv1a, extra: &BTreeMap<String, String>) {
+    if extra.is_empty() {
+        return;
+    }
+    hasher.write_len(extra.len());
+    for (key, value) in extra {
+        hasher.write_str(key);
+        hasher.write_str(value);
+    }
+}
+
+/// The page names in one null-terminated entry of the pages block: the entry
+/// itself, or its non-empty lines when an exporter separated the names with
+/// newlines instead of nulls. No genuine file name contains a newline.
+pub(crate) fn page_names(entry: &str) -> impl Iterator<Item = &str> {
+    let newline_separated = entry.contains('\n');
+    entry
+        .split('\n')
+        .map(move |name| {
+            if newline_separated {
+                name.trim_end_matches('\r')
+            } else {
+                name
+            }
+        })
+        .filter(move |name| !(newline_separated && name.is_empty()))
+}
+
+fn normalize_page_name(name: &str, prefix: &str) -> String {
+    let file_name = name.rsplit(['/', '\\']).next().unwrap_or(name);
+    let file_name = file_name.strip_prefix(prefix).unwrap_or(file_name);
+    match file_name.rsplit_once('.') {
+        Some((stem, extension)) => format!("{stem}.{}", extension.to_lowercase()),
+        None => file_name.to_string(),
+    }
+}
+
+#[derive(Debug, Clone, Default)]
 pub struct BMFont {
     pub info: Option<InfoBlock>,
     pub common: Option<CommonBlock>,
     pub pages: Vec<String>,
+    /// Page names exactly as stored in the file. Differs from `pages` only when
+    /// [`ParseOptions::normalize_page_names`] rewrote them.
+    pub raw_pages: Vec<String>,
     pub chars: HashMap<u32, Char>,
     pub kernings: Vec<KerningPair>,
+    /// Set by a lenient parse that stopped early because the input ended
+    /// inside a block.
+    pub truncated: bool,
+    /// Set when [`ParseOptions::max_glyphs`] cut the chars block short; the
+    /// font then holds only the first glyphs of the file.
+    pub truncated_glyphs: bool,
+    /// Whether the glyphs are signed distance fields needing an SDF shader.
+    /// The file format has no such flag, so this is never set by parsing; set
+    /// it yourself, possibly guided by [`BMFont::looks_like_sdf`].
+    pub is_sdf: bool,
+    /// Distance range the SDF glyphs were generated with, in pixels, when it
+    /// is known; takes precedence over the guess in [`BMFont::sdf_spread`].
+    pub sdf_spread_override: Option<f32>,
+    /// Blocks of types this crate does not know, as `(type, body)` in file
+    /// order. [`BMFont::write_to`] writes them back after the kerning block.
+    pub unknown_blocks: Vec<(u8, Vec<u8>)>,
+    /// Amount [`BMFont::kerning`] returns for pairs without an entry; 0 when
+    /// parsed. Measuring and layout take their kerning from there, so setting
+    /// it applies uniform tracking between consecutive glyphs on a line.
+    /// Pairs in the kerning table keep their own amount instead of adding
+    /// to it. It is not written out.
+    pub default_kerning: i16,
+    sorted_glyphs: OnceLock<Vec<Char>>,
+    line_metrics: OnceLock<Option<LineMetrics>>,
 }
 
-#[derive(Debug)]
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
 pub struct InfoBlock {
     pub font_size: i16,
     pub bit_field: u8,
@@ -23,9 +302,13 @@ pub struct InfoBlock {
     pub spacing: [u8; 2],
     pub outline: u8,
     pub font_name: String,
+    /// Attributes of a text or XML `info` line this crate does not know, such
+    /// as exporter extensions, kept so they can be written back out.
+    #[cfg(feature = "extra-attributes")]
+    pub extra: BTreeMap<String, String>,
 }
 
-#[derive(Debug)]
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
 pub struct CommonBlock {
     pub line_height: u16,
     pub base: u16,
@@ -39,7 +322,37 @@ pub struct CommonBlock {
     pub blue_chnl: u8,
 }
 
-#[derive(Debug)]
+/// A glyph id, the Unicode codepoint (or charset code) it renders. Lookups
+/// take `impl Into<GlyphId>` so both `'A'` and `65u32` work, while an
+/// unrelated integer such as an array index does not slip in unnoticed.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct GlyphId(pub u32);
+
+impl From<char> for GlyphId {
+    fn from(c: char) -> Self {
+        Self(c as u32)
+    }
+}
+
+impl From<u32> for GlyphId {
+    fn from(id: u32) -> Self {
+        Self(id)
+    }
+}
+
+impl From<GlyphId> for u32 {
+    fn from(id: GlyphId) -> Self {
+        id.0
+    }
+}
+
+/// Glyphs order by `id` only, not by all fields, so they can be sorted
+/// directly or kept in a `BTreeSet` and queried by id range. Equality and
+/// hashing still compare every field: two records with the same `id` but
+/// different rects order as neither less nor greater, yet are not `==`.
+/// Ids are unique within one font's `chars` map, so its glyphs never meet
+/// that case.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
 pub struct Char {
     pub id: u32,
     pub x: u16,
@@ -51,61 +364,523 @@ pub struct Char {
     pub x_advance: i16,
     pub page: u8,
     pub chnl: u8,
+    /// Attributes of a text or XML `char` line this crate does not know.
+    #[cfg(feature = "extra-attributes")]
+    pub extra: BTreeMap<String, String>,
 }
 
-#[derive(Debug)]
+impl PartialOrd for Char {
+    fn partial_cmp(&self, other: &Self) -> Option<std::cmp::Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for Char {
+    fn cmp(&self, other: &Self) -> std::cmp::Ordering {
+        self.id.cmp(&other.id)
+    }
+}
+
+/// Pixel rectangle within an atlas page.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Rect {
+    pub x: u16,
+    pub y: u16,
+    pub width: u16,
+    pub height: u16,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
 pub struct KerningPair {
     pub first: u32,
     pub second: u32,
     pub amount: i16,
 }
 
+/// Limits and behaviour switches for [`BMFont::from_octets_with_options`].
+#[derive(Debug, Clone)]
+pub struct ParseOptions {
+    /// Largest block, in bytes, the parser will allocate for. Inputs declaring
+    /// a bigger block fail with [`BmfError::BlockTooLarge`].
+    pub max_block_size: usize,
+    /// Salvage what can be read from a damaged file instead of failing: the
+    /// last, partial block keeps only its complete char or kerning records
+    /// (setting [`BMFont::truncated`]), names that are not UTF-8 are decoded
+    /// lossily, and other blocks that fail to parse are skipped.
+    pub lenient: bool,
+    /// Rewrite page names into portable file names: directory components are
+    /// dropped (both `/` and `\` count as separators), `strip_page_prefix` is
+    /// removed from the start of the file name, and the extension is
+    /// lowercased. `C:\art\menu_0.PNG` becomes `menu_0.png`.
+    pub normalize_page_names: bool,
+    /// Prefix removed from page file names when normalizing.
+    pub strip_page_prefix: Option<String>,
+    /// Read at most this many char records, for quick previews of huge fonts.
+    /// The rest of the chars block is skipped, so the font is incomplete and
+    /// has [`BMFont::truncated_glyphs`] set when records were left out.
+    pub max_glyphs: Option<usize>,
+    /// Most kerning pairs accepted; a bigger kerning block fails with
+    /// [`BmfError::TooManyKernings`]. The default of a million is well above
+    /// what real CJK fonts carry.
+    pub max_kerning_pairs: usize,
+}
+
+impl Default for ParseOptions {
+    fn default() -> Self {
+        Self {
+            max_block_size: 64 * 1024 * 1024,
+            lenient: false,
+            normalize_page_names: false,
+            strip_page_prefix: None,
+            max_glyphs: None,
+            max_kerning_pairs: 1_000_000,
+        }
+    }
+}
+
+impl InfoBlock {
+    pub fn smooth(&self) -> bool {
+        self.bit_field & 0b1000_0000 != 0
+    }
+
+    pub fn unicode(&self) -> bool {
+        self.bit_field & 0b0100_0000 != 0
+    }
+
+    pub fn italic(&self) -> bool {
+        self.bit_field & 0b0010_0000 != 0
+    }
+
+    pub fn bold(&self) -> bool {
+        self.bit_field & 0b0001_0000 != 0
+    }
+
+    pub fn fixed_height(&self) -> bool {
+        self.bit_field & 0b0000_1000 != 0
+    }
+
+    /// The size the font was requested at, without the sign BMFont uses to
+    /// mark [`InfoBlock::matches_char_height`].
+    pub fn requested_size(&self) -> u16 {
+        self.font_size.unsigned_abs()
+    }
+
+    /// Whether the requested size is the height of the characters rather than
+    /// of the character cell, which BMFont records as a negative `font_size`.
+    pub fn matches_char_height(&self) -> bool {
+        self.font_size < 0
+    }
+
+    /// Horizontal stretch as a factor, from the `stretch_h` percentage.
+    ///
+    /// Most fonts leave `stretch_h` at 100, giving 1.0. BMFont itself renders
+    /// the stretch into the glyphs and their metrics; a renderer only needs to
+    /// multiply horizontal metrics by this factor for exports that record a
+    /// stretch without applying it. A `stretch_h` of 0, written by exporters
+    /// that leave the field unset, is treated as 100 and gives 1.0.
+    pub fn stretch_factor(&self) -> f32 {
+        if self.stretch_h == 0 {
+            return 1.0;
+        }
+        self.stretch_h as f32 / 100.0
+    }
+
+    /// Supersampling level the glyphs were rendered with; 1 means none.
+    pub fn antialiasing_level(&self) -> u8 {
+        self.aa
+    }
+
+    /// Whether the glyphs were supersampled, in which case linear filtering
+    /// suits them better than nearest-neighbour.
+    pub fn is_antialiased(&self) -> bool {
+        self.aa > 1
+    }
+
+    /// Bits of `bit_field` the spec marks as reserved.
+    ///
+    /// The spec numbers bits from the most significant end (bit 0 is `smooth`),
+    /// so the reserved bits 5-7 are the three low-order bits of the byte.
+    /// A conforming exporter leaves them zero.
+    pub fn reserved_info_bits(&self) -> u8 {
+        self.bit_field & 0b0000_0111
+    }
+}
+
+impl CommonBlock {
+    /// Whether monochrome glyphs are packed into separate color channels.
+    /// Bit 7 in the spec's most-significant-first numbering.
+    pub fn packed(&self) -> bool {
+        self.bit_field & 0b0000_0001 != 0
+    }
+}
+
+impl Char {
+    /// Decodes one record of a version 3 chars block.
+    pub fn from_record(record: &[u8; CHAR_RECORD_SIZE]) -> Char {
+        let u16_at = |i: usize| u16::from_le_bytes([record[i], record[i + 1]]);
+        let i16_at = |i: usize| i16::from_le_bytes([record[i], record[i + 1]]);
+        Char {
+            id: u32::from_le_bytes([record[0], record[1], record[2], record[3]]),
+            x: u16_at(4),
+            y: u16_at(6),
+            width: u16_at(8),
+            height: u16_at(10),
+            x_offset: i16_at(12),
+            y_offset: i16_at(14),
+            x_advance: i16_at(16),
+            page: record[18],
+            chnl: record[19],
+            #[cfg(feature = "extra-attributes")]
+            extra: BTreeMap::new(),
+        }
+    }
+
+    /// The glyph as a record of a version 3 chars block, the inverse of
+    /// [`Char::from_record`].
+    pub fn to_record(&self) -> [u8; CHAR_RECORD_SIZE] {
+        let mut record = [0; CHAR_RECORD_SIZE];
+        record[0..4].copy_from_slice(&self.id.to_le_bytes());
+        record[4..6].copy_from_slice(&self.x.to_le_bytes());
+        record[6..8].copy_from_slice(&self.y.to_le_bytes());
+        record[8..10].copy_from_slice(&self.width.to_le_bytes());
+        record[10..12].copy_from_slice(&self.height.to_le_bytes());
+        record[12..14].copy_from_slice(&self.x_offset.to_le_bytes());
+        record[14..16].copy_from_slice(&self.y_offset.to_le_bytes());
+        record[16..18].copy_from_slice(&self.x_advance.to_le_bytes());
+        record[18] = self.page;
+        record[19] = self.chnl;
+        record
+    }
+
+    /// Source rectangle of the glyph on its page.
+    pub fn rect(&self) -> Rect {
+        Rect {
+            x: self.x,
+            y: self.y,
+            width: self.width,
+            height: self.height,
+        }
+    }
+
+    /// Width divided by height, or 0.0 for a glyph with zero height.
+    pub fn aspect_ratio(&self) -> f32 {
+        if self.height == 0 {
+            return 0.0;
+        }
+        self.width as f32 / self.height as f32
+    }
+
+    /// Pixel area of the glyph's rect.
+    pub fn area(&self) -> u32 {
+        self.width as u32 * self.height as u32
+    }
+}
+
 impl BMFont {
-    pub fn from_octets(data: &[u8]) -> io::Result<Self> {
-        let mut cursor = Cursor::new(data);
+    pub fn from_octets(data: &[u8]) -> Result<Self, BmfError> {
+        Self::from_octets_with_options(data, &ParseOptions::default())
+    }
+
+    pub fn from_octets_with_options(data: &[u8], options: &ParseOptions) -> Result<Self, BmfError> {
+        Self::parse_prefix(data, options, false, &mut Vec::new()).map(|(font, _)| font)
+    }
+
+    /// Parses a font in any of the three BMFont formats, telling them apart
+    /// by content: the binary magic, else `<` opening the XML format, else
+    /// the text format. A leading UTF-8 byte order mark is ignored. Text that
+    /// is not UTF-8 fails with [`BmfError::InvalidHeader`].
+    pub fn parse(data: &[u8]) -> Result<Self, BmfError> {
+        if is_bmf(data) {
+            return Self::from_octets(data);
+        }
+        let text = std::str::from_utf8(data).map_err(|_| BmfError::InvalidHeader)?;
+        let text = text.strip_prefix('\u{FEFF}').unwrap_or(text);
+        if text.trim_start().starts_with('<') {
+            Self::from_xml(text)
+        } else {
+            Self::from_text(text)
+        }
+    }
+
+    /// Parses a font that starts `offset` bytes into `data`, such as one
+    /// embedded in an asset bundle behind the bundle's own header. Fails with
+    /// [`BmfError::InvalidHeader`] if there is no font magic at `offset`.
+    pub fn from_octets_at(data: &[u8], offset: usize) -> Result<Self, BmfError> {
+        let data = data.get(offset..).ok_or(BmfError::InvalidHeader)?;
+        Self::from_octets(data)
+    }
+
+    /// Like [`BMFont::from_octets`], but fails with [`BmfError::MissingBlock`]
+    /// unless the font has both an info and a common block, so renderers can
+    /// rely on `info` and `common` being `Some`.
+    pub fn from_octets_strict(data: &[u8]) -> Result<Self, BmfError> {
+        let font = Self::from_octets(data)?;
+        if font.info.is_none() {
+            return Err(BmfError::MissingBlock(BlockKind::Info));
+        }
+        if font.common.is_none() {
+            return Err(BmfError::MissingBlock(BlockKind::Common));
+        }
+        Ok(font)
+    }
 
-        if cursor.read_u8()? != 66
-            || cursor.read_u8()? != 77
-            || cursor.read_u8()? != 70
-            || cursor.read_u8()? != 3
-        {
-            return Err(io::Error::new(
-                io::ErrorKind::InvalidData,
-                "Invalid BMFont header",
-            ));
+    /// Parses as much of `data` as possible, reporting every imperfection as a
+    /// [`ParseWarning`] instead of failing on the first one. Returns `None`
+    /// only when not even a partial font could be read, with the reason as
+    /// the last warning.
+    pub fn from_octets_lenient(data: &[u8]) -> (Option<Self>, Vec<ParseWarning>) {
+        let options = ParseOptions {
+            lenient: true,
+            ..Default::default()
+        };
+        let mut warnings = Vec::new();
+        let font = match Self::parse_prefix(data, &options, false, &mut warnings) {
+            Ok((font, _)) => font,
+            Err(e) => {
+                warnings.push(ParseWarning::Failed(e));
+                return (None, warnings);
+            }
+        };
+
+        if font.truncated {
+            warnings.push(ParseWarning::Truncated);
+        }
+        if let Some(common) = &font.common {
+            if common.pages as usize != font.pages.len() {
+                warnings.push(ParseWarning::PageCountMismatch {
+                    declared: common.pages,
+                    actual: font.pages.len(),
+                });
+            }
         }
+        let mut out_of_range: Vec<&Char> = font
+            .chars
+            .values()
+            .filter(|ch| ch.page as usize >= font.pages.len())
+            .collect();
+        out_of_range.sort_by_key(|ch| ch.id);
+        warnings.extend(
+            out_of_range
+                .into_iter()
+                .map(|ch| ParseWarning::PageOutOfRange {
+                    id: ch.id,
+                    page: ch.page,
+                }),
+        );
+
+        (Some(font), warnings)
+    }
+
+    /// Parses several fonts concatenated back to back, returning each font with
+    /// the byte range it occupied in `data`.
+    pub fn from_octets_multi(data: &[u8]) -> Result<Vec<(Self, Range<usize>)>, BmfError> {
+        let mut fonts = Vec::new();
+        let mut start = 0;
+        while start < data.len() {
+            let (font, len) = Self::parse_prefix(
+                &data[start..],
+                &ParseOptions::default(),
+                true,
+                &mut Vec::new(),
+            )?;
+            fonts.push((font, start..start + len));
+            start += len;
+        }
+        Ok(fonts)
+    }
+
+    /// Parses one font from the start of `data`, stopping at the end of the
+    /// buffer or, with `stop_at_magic`, where the magic of a following font
+    /// begins; otherwise bytes after the last block must form blocks too.
+    /// Returns the font
+    /// and the number of bytes it used. Unknown blocks, and in lenient mode
+    /// the blocks it had to skip or patch up, are reported in `warnings`.
+    ///
+    /// Blocks may come in any order: each is parsed on its own, and nothing
+    /// that combines them, such as page name normalization, runs before the
+    /// last one has been read.
+    fn parse_prefix(
+        data: &[u8],
+        options: &ParseOptions,
+        stop_at_magic: bool,
+        warnings: &mut Vec<ParseWarning>,
+    ) -> Result<(Self, usize), BmfError> {
+        let (font, chars, len) = Self::parse_blocks(data, options, stop_at_magic, warnings)?;
+        Ok((Self { chars, ..font }, len))
+    }
+
+    /// [`BMFont::parse_prefix`] with the glyphs read into a `G` instead of
+    /// `chars`, which is left empty.
+    pub(crate) fn parse_blocks<G: GlyphTable>(
+        data: &[u8],
+        options: &ParseOptions,
+        stop_at_magic: bool,
+        warnings: &mut Vec<ParseWarning>,
+    ) -> Result<(Self, G, usize), BmfError> {
+        if !is_bmf(data) {
+            return Err(BmfError::InvalidHeader);
+        }
+        let mut cursor = Cursor::new(data);
+        cursor.set_position(4);
 
         let mut info = None;
         let mut common = None;
         let mut pages = Vec::new();
-        let mut chars = HashMap::new();
+        let mut chars = G::default();
         let mut kernings = Vec::new();
+        let mut unknown_blocks = Vec::new();
 
-        while let Ok(block_type) = cursor.read_u8() {
-            let block_size = cursor.read_u32::<LittleEndian>()? as usize;
-            let mut block_data = vec![0; block_size];
-            cursor.read_exact(&mut block_data)?;
-
-            match block_type {
-                1 => info = Some(Self::parse_info_block(&block_data)?),
-                2 => common = Some(Self::parse_common_block(&block_data)?),
-                3 => pages = Self::parse_pages_block(&block_data)?,
-                4 => chars = Self::parse_chars_block(&block_data)?,
-                5 => kernings = Self::parse_kerning_block(&block_data)?,
-                _ => (),
+        let mut truncated = false;
+        let mut truncated_glyphs = false;
+
+        while !(stop_at_magic && is_bmf(&data[cursor.position() as usize..])) {
+            let Ok(block_type) = cursor.read_u8() else {
+                break;
+            };
+            let block_size = match cursor.read_u32::<LittleEndian>() {
+                Ok(size) => size as usize,
+                Err(_) if options.lenient => {
+                    truncated = true;
+                    break;
+                }
+                Err(e) => return Err(e.into()),
+            };
+            if block_size > options.max_block_size {
+                return Err(BmfError::BlockTooLarge {
+                    block: block_type,
+                    size: block_size,
+                });
+            }
+            if block_type == 5 && block_size / KERNING_RECORD_SIZE > options.max_kerning_pairs {
+                return Err(BmfError::TooManyKernings {
+                    count: block_size / KERNING_RECORD_SIZE,
+                    max: options.max_kerning_pairs,
+                });
+            }
+            let start = cursor.position() as usize;
+            let available = data.len() - start;
+            let mut block_data = if block_size <= available {
+                &data[start..start + block_size]
+            } else if options.lenient {
+                truncated = true;
+                &data[start..]
+            } else {
+                return Err(io::Error::from(io::ErrorKind::UnexpectedEof).into());
+            };
+            cursor.set_position((start + block_data.len()) as u64);
+
+            if truncated {
+                // Only the record-based blocks can be salvaged from a partial
+                // block; drop the incomplete record at the end.
+                let record_size = match block_type {
+                    4 => char_record_size(data[3]),
+                    5 => KERNING_RECORD_SIZE,
+                    _ => break,
+                };
+                block_data = &block_data[..block_data.len() - block_data.len() % record_size];
+            }
+
+            let parsed = match block_type {
+                1 => Self::parse_info_block(block_data, false).map(|i| info = Some(i)),
+                2 => Self::parse_common_block(block_data).map(|c| common = Some(c)),
+                3 => Self::parse_pages_block(block_data, false).map(|p| pages = p),
+                4 => {
+                    let record_size = char_record_size(data[3]);
+                    let records = match options.max_glyphs {
+                        Some(max) if block_data.len() / record_size > max => {
+                            truncated_glyphs = true;
+                            &block_data[..max * record_size]
+                        }
+                        _ => block_data,
+                    };
+                    let record_count = records.len() / record_size;
+                    if record_count > 1 && records.iter().all(|b| *b == 0) {
+                        warnings.push(ParseWarning::ZeroedCharsBlock {
+                            records: record_count,
+                        });
+                    }
+                    G::from_records(records, record_size).map(|c| {
+                        if c.len() < record_count {
+                            warnings.push(ParseWarning::DuplicateGlyphIds {
+                                records: record_count,
+                                distinct: c.len(),
+                            });
+                        }
+                        chars = c;
+                    })
+                }
+                5 => Self::parse_kerning_block(block_data).map(|k| kernings = k),
+                _ => {
+                    warnings.push(ParseWarning::UnknownBlock { block: block_type });
+                    unknown_blocks.push((block_type, block_data.to_vec()));
+                    Ok(())
+                }
+            };
+            match parsed {
+                Ok(()) => (),
+                Err(e)
+                    if options.lenient
+                        && matches!(block_type, 1 | 3)
+                        && e.kind() == io::ErrorKind::InvalidData =>
+                {
+                    // Names that are not UTF-8; keep them with replacement characters.
+                    warnings.push(ParseWarning::InvalidUtf8 { block: block_type });
+                    match block_type {
+                        1 => info = Some(Self::parse_info_block(block_data, true)?),
+                        _ => pages = Self::parse_pages_block(block_data, true)?,
+                    }
+                }
+                Err(e) if options.lenient => warnings.push(ParseWarning::InvalidBlock {
+                    block: block_type,
+                    message: e.to_string(),
+                }),
+                Err(e) => return Err(e.into()),
+            }
+        }
+
+        let raw_pages = pages.clone();
+        if options.normalize_page_names {
+            let prefix = options.strip_page_prefix.as_deref().unwrap_or("");
+            for page in &mut pages {
+                *page = normalize_page_name(page, prefix);
             }
         }
 
-        Ok(Self {
+        let font = Self {
             info,
             common,
             pages,
-            chars,
+            raw_pages,
             kernings,
-        })
+            truncated,
+            truncated_glyphs,
+            unknown_blocks,
+            ..Self::new()
+        };
+        Ok((font, chars, cursor.position() as usize))
+    }
+
+    pub fn from_file(path: &Path) -> Result<Self, BmfError> {
+        Self::from_octets(&fs::read(path)?)
     }
 
-    fn parse_info_block(data: &[u8]) -> io::Result<InfoBlock> {
+    /// Parses the font at `path` and resolves its page textures relative to the
+    /// font's directory, failing with [`BmfError::MissingPage`] if any is absent.
+    pub fn load_with_pages(path: &Path) -> Result<(Self, Vec<PathBuf>), BmfError> {
+        let font = Self::from_file(path)?;
+        let dir = path.parent().unwrap_or_else(|| Path::new(""));
+        let mut page_paths = Vec::with_capacity(font.pages.len());
+        for page in &font.pages {
+            let page_path = dir.join(page);
+            if !page_path.is_file() {
+                return Err(BmfError::MissingPage(page_path));
+            }
+            page_paths.push(page_path);
+        }
+        Ok((font, page_paths))
+    }
+
+    fn parse_info_block(data: &[u8], lossy: bool) -> io::Result<InfoBlock> {
         let mut cursor = Cursor::new(data);
         Ok(InfoBlock {
             font_size: cursor.read_i16::<LittleEndian>()?,
@@ -124,15 +899,18 @@ impl BMFont {
             font_name: {
                 let mut font_name = Vec::new();
                 cursor.read_to_end(&mut font_name)?;
-                String::from_utf8(font_name)
-                    .map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))?
-                    .trim_end_matches('\0')
-                    .to_string()
+                // The name is a C string; anything after the first null is padding.
+                if let Some(end) = font_name.iter().position(|&b| b == 0) {
+                    font_name.truncate(end);
+                }
+                decode_name(font_name, lossy)?
             },
+            #[cfg(feature = "extra-attributes")]
+            extra: BTreeMap::new(),
         })
     }
 
-    fn parse_common_block(data: &[u8]) -> io::Result<CommonBlock> {
+    pub(crate) fn parse_common_block(data: &[u8]) -> io::Result<CommonBlock> {
         let mut cursor = Cursor::new(data);
         Ok(CommonBlock {
             line_height: cursor.read_u16::<LittleEndian>()?,
@@ -141,51 +919,55 @@ impl BMFont {
             scale_h: cursor.read_u16::<LittleEndian>()?,
             pages: cursor.read_u16::<LittleEndian>()?,
             bit_field: cursor.read_u8()?,
-            alpha_chnl: cursor.read_u8()?,
-            red_chnl: cursor.read_u8()?,
-            green_chnl: cursor.read_u8()?,
-            blue_chnl: cursor.read_u8()?,
+            // Minimal exporters stop after `bit_field`; missing channels read as 0.
+            alpha_chnl: cursor.read_u8().unwrap_or(0),
+            red_chnl: cursor.read_u8().unwrap_or(0),
+            green_chnl: cursor.read_u8().unwrap_or(0),
+            blue_chnl: cursor.read_u8().unwrap_or(0),
         })
     }
 
-    fn parse_pages_block(data: &[u8]) -> io::Result<Vec<String>> {
+    /// Null-terminated page names. A missing terminator after the last name
+    /// is tolerated, as are names separated by newlines.
+    fn parse_pages_block(data: &[u8], lossy: bool) -> io::Result<Vec<String>> {
         let mut cursor = Cursor::new(data);
         let mut pages = Vec::new();
         while cursor.position() < data.len() as u64 {
             let mut page_name = Vec::new();
             cursor.read_until(0, &mut page_name)?;
-            pages.push(
-                String::from_utf8(page_name)
-                    .map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))?
-                    .trim_end_matches('\0')
-                    .to_string(),
-            );
+            let page_name = decode_name(page_name, lossy)?;
+            let page_name = page_name.trim_end_matches('\0');
+            pages.extend(page_names(page_name).map(str::to_string));
         }
         Ok(pages)
     }
 
-    fn parse_chars_block(data: &[u8]) -> io::Result<HashMap<u32, Char>> {
-        let mut cursor = Cursor::new(data);
+    pub(crate) fn parse_chars_block(
+        data: &[u8],
+        record_size: usize,
+    ) -> io::Result<HashMap<u32, Char>> {
         let mut chars = HashMap::new();
-        while cursor.position() < data.len() as u64 {
-            let ch = Char {
-                id: cursor.read_u32::<LittleEndian>()?,
-                x: cursor.read_u16::<LittleEndian>()?,
-                y: cursor.read_u16::<LittleEndian>()?,
-                width: cursor.read_u16::<LittleEndian>()?,
-                height: cursor.read_u16::<LittleEndian>()?,
-                x_offset: cursor.read_i16::<LittleEndian>()?,
-                y_offset: cursor.read_i16::<LittleEndian>()?,
-                x_advance: cursor.read_i16::<LittleEndian>()?,
-                page: cursor.read_u8()?,
-                chnl: cursor.read_u8()?,
-            };
+        for record in data.chunks(record_size) {
+            if record.len() < record_size {
+                return Err(io::ErrorKind::UnexpectedEof.into());
+            }
+            let ch = Self::parse_char_record(record)?;
             chars.insert(ch.id, ch);
         }
         Ok(chars)
     }
 
-    fn parse_kerning_block(data: &[u8]) -> io::Result<Vec<KerningPair>> {
+    /// Reads the fields every known version starts a char record with. Bytes a
+    /// newer version appends after them are ignored.
+    fn parse_char_record(record: &[u8]) -> io::Result<Char> {
+        let record = record
+            .get(..CHAR_RECORD_SIZE)
+            .and_then(|fields| fields.try_into().ok())
+            .ok_or(io::ErrorKind::UnexpectedEof)?;
+        Ok(Char::from_record(record))
+    }
+
+    pub(crate) fn parse_kerning_block(data: &[u8]) -> io::Result<Vec<KerningPair>> {
         let mut cursor = Cursor::new(data);
         let mut kernings = Vec::new();
         while cursor.position() < data.len() as u64 {
@@ -198,3 +980,634 @@ impl BMFont {
         Ok(kernings)
     }
 }
+
+impl BMFont {
+    /// An empty font with no blocks, glyphs or kerning pairs, to be filled in
+    /// field by field.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// The face name from the info block, if the font has one.
+    pub fn face_name(&self) -> Option<&str> {
+        self.info.as_ref().map(|info| info.font_name.as_str())
+    }
+
+    /// [`BMFont::face_name`], or `default` for a font without an info block.
+    pub fn face_name_or<'a>(&'a self, default: &'a str) -> &'a str {
+        self.face_name().unwrap_or(default)
+    }
+
+    /// Body of the first preserved block of type `block_type`, for metadata
+    /// such as provenance tags that tools store in blocks of their own type.
+    /// See [`BMFont::unknown_blocks`].
+    pub fn custom_block(&self, block_type: u8) -> Option<&[u8]> {
+        self.unknown_blocks
+            .iter()
+            .find(|(kind, _)| *kind == block_type)
+            .map(|(_, body)| body.as_slice())
+    }
+
+    /// Glyph for a character or codepoint, if the font has one.
+    pub fn glyph(&self, id: impl Into<GlyphId>) -> Option<&Char> {
+        self.chars.get(&id.into().0)
+    }
+
+    /// Kerning amount between two glyphs, or [`BMFont::default_kerning`] if
+    /// the pair has no entry.
+    pub fn kerning(&self, first: impl Into<GlyphId>, second: impl Into<GlyphId>) -> i16 {
+        let (GlyphId(first), GlyphId(second)) = (first.into(), second.into());
+        self.kernings
+            .iter()
+            .find(|k| k.first == first && k.second == second)
+            .map_or(self.default_kerning, |k| k.amount)
+    }
+
+    /// Normalized texture coordinates `[u0, v0, u1, v1]` of a glyph within its page.
+    ///
+    /// Returns `None` if the font has no common block or the atlas size is zero.
+    pub fn uv_rect(&self, ch: &Char) -> Option<[f32; 4]> {
+        let common = self.common.as_ref()?;
+        self.uv_rect_with_size(ch, common.scale_w as u32, common.scale_h as u32)
+    }
+
+    /// Like [`BMFont::uv_rect`], but against a caller-supplied atlas size, for
+    /// fonts without a common block. Returns `None` if either size is zero.
+    pub fn uv_rect_with_size(&self, ch: &Char, width: u32, height: u32) -> Option<[f32; 4]> {
+        if width == 0 || height == 0 {
+            return None;
+        }
+        let w = width as f32;
+        let h = height as f32;
+        Some([
+            ch.x as f32 / w,
+            ch.y as f32 / h,
+            (ch.x as f32 + ch.width as f32) / w,
+            (ch.y as f32 + ch.height as f32) / h,
+        ])
+    }
+
+    /// UV rect against the size of the texture actually loaded for the glyph's
+    /// page. Use this instead of [`BMFont::uv_rect`] when textures were padded
+    /// (for example to a power of two) and no longer match `scale_w`/`scale_h`.
+    /// A zero-sized texture gives an all-zero rect.
+    pub fn uv_rect_for_texture(&self, ch: &Char, tex_w: u32, tex_h: u32) -> [f32; 4] {
+        self.uv_rect_with_size(ch, tex_w, tex_h).unwrap_or_default()
+    }
+
+    /// Texture array layer and UV rect of a glyph, for renderers that bind all
+    /// pages as one texture array. The layer is the glyph's `page`.
+    pub fn layered_uv(&self, ch: &Char) -> Option<(u32, [f32; 4])> {
+        Some((ch.page as u32, self.uv_rect(ch)?))
+    }
+
+    /// Splits the font into one single-page font per entry in `pages`.
+    ///
+    /// Each result keeps only the glyphs on that page, with `page` reset to 0,
+    /// and the kerning pairs whose glyphs are both on that page.
+    pub fn split_by_page(&self) -> Vec<BMFont> {
+        self.pages
+            .iter()
+            .enumerate()
+            .map(|(index, page_name)| {
+                let chars: HashMap<u32, Char> = self
+                    .chars
+                    .values()
+                    .filter(|ch| ch.page as usize == index)
+                    .map(|ch| {
+                        let mut ch = ch.clone();
+                        ch.page = 0;
+                        (ch.id, ch)
+                    })
+                    .collect();
+                let kernings = self
+                    .kernings
+                    .iter()
+                    .filter(|k| chars.contains_key(&k.first) && chars.contains_key(&k.second))
+                    .cloned()
+                    .collect();
+                let common = self.common.clone().map(|mut common| {
+                    common.pages = 1;
+                    common
+                });
+
+                BMFont {
+                    common,
+                    pages: vec![page_name.clone()],
+                    raw_pages: self.raw_pages.get(index).cloned().into_iter().collect(),
+                    ..self.with_glyphs(chars, kernings)
+                }
+            })
+            .collect()
+    }
+
+    pub fn coverage_bitset(&self) -> CoverageSet {
+        CoverageSet::from_ids(self.chars.keys().copied())
+    }
+
+    /// Re-keys `chars` by each glyph's current `Char::id`, after ids were
+    /// edited in place through `chars.values_mut()`. If several glyphs now share
+    /// an id, the one that was stored under that id (or else under the lowest
+    /// old key) is kept.
+    pub fn rebuild_char_index(&mut self) {
+        let mut glyphs: Vec<(u32, Char)> = self.chars.drain().collect();
+        glyphs.sort_by_key(|(old_id, ch)| (ch.id, *old_id != ch.id, *old_id));
+        for (_, ch) in glyphs {
+            self.chars.entry(ch.id).or_insert(ch);
+        }
+        self.invalidate_sorted_glyphs();
+    }
+
+    /// Removes all kerning pairs, leaving the rest of the font intact.
+    pub fn clear_kerning(&mut self) {
+        self.kernings.clear();
+    }
+
+    /// Replaces all kerning pairs with `(first, second, amount)` triples, for
+    /// example kerning taken from the source TrueType font.
+    pub fn set_kernings(&mut self, pairs: impl IntoIterator<Item = (u32, u32, i16)>) {
+        self.kernings = pairs
+            .into_iter()
+            .map(|(first, second, amount)| KerningPair {
+                first,
+                second,
+                amount,
+            })
+            .collect();
+    }
+
+    /// Kerning pairs with `id` on either side, in table order.
+    pub fn kernings_involving(&self, id: u32) -> impl Iterator<Item = &KerningPair> {
+        self.kernings
+            .iter()
+            .filter(move |k| k.first == id || k.second == id)
+    }
+
+    /// Pairs whose kerning differs between the two orders, as `(a, b)` with
+    /// `a < b`, sorted. A pair missing in one order counts as kerned by
+    /// [`BMFont::default_kerning`] there, as in [`BMFont::kerning`].
+    pub fn asymmetric_kernings(&self) -> Vec<(u32, u32)> {
+        let mut amounts: HashMap<(u32, u32), i16> = HashMap::new();
+        for k in &self.kernings {
+            amounts.entry((k.first, k.second)).or_insert(k.amount);
+        }
+        let mut pairs: Vec<(u32, u32)> = amounts
+            .iter()
+            .filter(|&(&(first, second), &amount)| {
+                amounts
+                    .get(&(second, first))
+                    .copied()
+                    .unwrap_or(self.default_kerning)
+                    != amount
+            })
+            .map(|(&(first, second), _)| (first.min(second), first.max(second)))
+            .collect();
+        pairs.sort_unstable();
+        pairs.dedup();
+        pairs
+    }
+
+    /// Best estimate of the size in pixels the font was rendered at.
+    ///
+    /// A positive `info.font_size` is the em size BMFont rendered with and is
+    /// returned as is. BMFont stores a negative `font_size` when the size was
+    /// chosen to match the character height rather than the cell height, and
+    /// some exporters write 0; in both cases the size is estimated from
+    /// `common.line_height`, or else from the tallest glyph. `None` if there
+    /// is nothing to estimate from.
+    pub fn effective_pixel_size(&self) -> Option<u16> {
+        if let Some(size) = self.info.as_ref().map(|info| info.font_size) {
+            if size > 0 {
+                return Some(size as u16);
+            }
+        }
+        let line_height = self.common.as_ref().map_or(0, |c| c.line_height);
+        [line_height, self.max_glyph_height()]
+            .into_iter()
+            .find(|size| *size > 0)
+    }
+
+    /// Deletes the glyphs in `ids` together with every kerning pair that
+    /// refers to one of them on either side, so no kerning is left dangling.
+    /// Returns how many kerning pairs were dropped.
+    pub fn remove_glyphs(&mut self, ids: &HashSet<u32>) -> usize {
+        self.chars.retain(|id, _| !ids.contains(id));
+        let before = self.kernings.len();
+        self.kernings
+            .retain(|k| !ids.contains(&k.first) && !ids.contains(&k.second));
+        self.invalidate_sorted_glyphs();
+        before - self.kernings.len()
+    }
+
+    /// Height of the tallest glyph, or 0 for a font without glyphs.
+    pub fn max_glyph_height(&self) -> u16 {
+        self.chars.values().map(|ch| ch.height).max().unwrap_or(0)
+    }
+
+    /// Width of the widest glyph, or 0 for a font without glyphs.
+    pub fn max_glyph_width(&self) -> u16 {
+        self.chars.values().map(|ch| ch.width).max().unwrap_or(0)
+    }
+
+    /// Stable hash of the font's contents, independent of the order glyphs and
+    /// kerning pairs were stored in. Equal fonts hash equally across runs,
+    /// targets and toolchains, so the hash can key a persistent cache: every
+    /// field is fed in as fixed-width little-endian bytes, with `u64` length
+    /// prefixes and a presence byte for the optional blocks.
+    pub fn content_hash(&self) -> u64 {
+        let mut hasher = hash::Fnv1a::default();
+        match &self.info {
+            Some(info) => {
+                hasher.write_u8(1);
+                hasher.write_i16(info.font_size);
+                hasher.write_u8(info.bit_field);
+                hasher.write_u8(info.char_set);
+                hasher.write_u16(info.stretch_h);
+                hasher.write_u8(info.aa);
+                hasher.write(&info.padding);
+                hasher.write(&info.spacing);
+                hasher.write_u8(info.outline);
+                hasher.write_str(&info.font_name);
+                #[cfg(feature = "extra-attributes")]
+                hash_extra(&mut hasher, &info.extra);
+            }
+            None => hasher.write_u8(0),
+        }
+        match &self.common {
+            Some(common) => {
+                hasher.write_u8(1);
+                hasher.write_u16(common.line_height);
+                hasher.write_u16(common.base);
+                hasher.write_u16(common.scale_w);
+                hasher.write_u16(common.scale_h);
+                hasher.write_u16(common.pages);
+                hasher.write_u8(common.bit_field);
+                hasher.write_u8(common.alpha_chnl);
+                hasher.write_u8(common.red_chnl);
+                hasher.write_u8(common.green_chnl);
+                hasher.write_u8(common.blue_chnl);
+            }
+            None => hasher.write_u8(0),
+        }
+        hasher.write_len(self.pages.len());
+        for page in &self.pages {
+            hasher.write_str(page);
+        }
+
+        let mut chars: Vec<&Char> = self.chars.values().collect();
+        chars.sort_by_key(|ch| ch.id);
+        hasher.write_len(chars.len());
+        for ch in chars {
+            hasher.write(&ch.to_record());
+            #[cfg(feature = "extra-attributes")]
+            hash_extra(&mut hasher, &ch.extra);
+        }
+
+        let mut kernings: Vec<&KerningPair> = self.kernings.iter().collect();
+        kernings.sort_by_key(|k| (k.first, k.second, k.amount));
+        hasher.write_len(kernings.len());
+        for k in kernings {
+            hasher.write_u32(k.first);
+            hasher.write_u32(k.second);
+            hasher.write_i16(k.amount);
+        }
+
+        hasher.finish()
+    }
+
+    /// Source rects of the glyphs in `used`, largest area first, ready to feed
+    /// a bin packer. Ids without a glyph are skipped.
+    pub fn used_rects(&self, used: &HashSet<u32>) -> Vec<(u32, Rect)> {
+        let mut rects: Vec<(u32, Rect)> = used
+            .iter()
+            .filter_map(|id| self.chars.get(id))
+            .map(|ch| (ch.id, ch.rect()))
+            .collect();
+        rects.sort_by_key(|(id, rect)| {
+            (
+                std::cmp::Reverse(rect.width as u32 * rect.height as u32),
+                *id,
+            )
+        });
+        rects
+    }
+
+    /// Pixels by which the lowest glyph bottom (`y_offset + height`) extends
+    /// past `line_height`, or `None` if every glyph fits its line box or the
+    /// font has no common block.
+    pub fn line_height_overflow(&self) -> Option<i32> {
+        let line_height = self.common.as_ref()?.line_height as i32;
+        let bottom = self
+            .chars
+            .values()
+            .map(|ch| ch.y_offset as i32 + ch.height as i32)
+            .max()?;
+        (bottom > line_height).then_some(bottom - line_height)
+    }
+
+    /// Copy of the font keeping only the glyphs in `keep` and the kerning pairs
+    /// between them. Pages are left untouched so `Char::page` stays valid.
+    pub fn subset(&self, keep: &HashSet<u32>) -> BMFont {
+        let chars = self
+            .chars
+            .iter()
+            .filter(|(id, _)| keep.contains(id))
+            .map(|(id, ch)| (*id, ch.clone()))
+            .collect();
+        let kernings = self
+            .kernings
+            .iter()
+            .filter(|k| keep.contains(&k.first) && keep.contains(&k.second))
+            .cloned()
+            .collect();
+        self.with_glyphs(chars, kernings)
+    }
+
+    /// Copy of everything but the glyphs and kerning pairs, which are
+    /// replaced by `chars` and `kernings`.
+    fn with_glyphs(&self, chars: HashMap<u32, Char>, kernings: Vec<KerningPair>) -> BMFont {
+        BMFont {
+            info: self.info.clone(),
+            common: self.common.clone(),
+            pages: self.pages.clone(),
+            raw_pages: self.raw_pages.clone(),
+            chars,
+            kernings,
+            truncated: self.truncated,
+            truncated_glyphs: self.truncated_glyphs,
+            is_sdf: self.is_sdf,
+            sdf_spread_override: self.sdf_spread_override,
+            unknown_blocks: self.unknown_blocks.clone(),
+            default_kerning: self.default_kerning,
+            sorted_glyphs: OnceLock::new(),
+            line_metrics: OnceLock::new(),
+        }
+    }
+
+    /// Like [`BMFont::subset`], keeping every glyph whose id falls in one of `ranges`.
+    pub fn subset_ranges(&self, ranges: &[RangeInclusive<u32>]) -> BMFont {
+        let keep = self
+            .chars
+            .keys()
+            .filter(|id| ranges.iter().any(|range| range.contains(id)))
+            .copied()
+            .collect();
+        self.subset(&keep)
+    }
+
+    /// File name of the atlas texture for `page`.
+    pub fn page_file(&self, page: u8) -> Option<&str> {
+        self.pages.get(page as usize).map(String::as_str)
+    }
+
+    pub fn glyphs_on_page(&self, page: u8) -> impl Iterator<Item = &Char> {
+        self.chars.values().filter(move |ch| ch.page == page)
+    }
+
+    /// How far a glyph reaches above and below the baseline, as
+    /// `(above, below)` in pixels.
+    ///
+    /// `y_offset` is measured down from the top of the line and the baseline
+    /// sits `common.base` pixels below that top, so `above = base - y_offset`
+    /// and `below = y_offset + height - base`. Both are positive when the glyph
+    /// crosses the baseline; a glyph floating above it (an apostrophe) has a
+    /// negative `below`. Without a common block the baseline is taken as 0.
+    pub fn glyph_vertical_extent(&self, ch: &Char) -> (i32, i32) {
+        let base = self.common.as_ref().map_or(0, |c| c.base as i32);
+        let top = ch.y_offset as i32;
+        (base - top, top + ch.height as i32 - base)
+    }
+
+    /// Pairs of glyphs whose rects overlap on the same page, as `(a, b)` with
+    /// `a < b`, sorted. Glyphs with an empty rect cannot overlap anything.
+    pub fn overlapping_glyphs(&self) -> Vec<(u32, u32)> {
+        let mut glyphs: Vec<&Char> = self.chars.values().filter(|ch| ch.area() > 0).collect();
+        glyphs.sort_by_key(|ch| (ch.page, ch.x));
+        let mut pairs = Vec::new();
+        for (index, a) in glyphs.iter().enumerate() {
+            // Sorted by x within the page, so stop at the first glyph that
+            // starts right of `a`.
+            for b in glyphs[index + 1..]
+                .iter()
+                .take_while(|b| b.page == a.page && (b.x as u32) < a.x as u32 + a.width as u32)
+            {
+                if (b.y as u32) < a.y as u32 + a.height as u32
+                    && (a.y as u32) < b.y as u32 + b.height as u32
+                {
+                    pairs.push((a.id.min(b.id), a.id.max(b.id)));
+                }
+            }
+        }
+        pairs.sort_unstable();
+        pairs
+    }
+
+    /// Glyphs that draw no pixels because their width or height is zero, in
+    /// no particular order. Spaces are among them; checking the id with
+    /// `char::is_whitespace` tells them apart from stray empty glyphs.
+    pub fn empty_glyphs(&self) -> impl Iterator<Item = &Char> {
+        self.chars
+            .values()
+            .filter(|ch| ch.width == 0 || ch.height == 0)
+    }
+
+    /// The distinct `page` values glyphs are sampled from, so a loader can
+    /// skip atlas textures no glyph uses.
+    pub fn referenced_pages(&self) -> HashSet<u8> {
+        self.chars.values().map(|ch| ch.page).collect()
+    }
+
+    /// Names of the pages no glyph references, in page order, such as the
+    /// leftovers of a subsetted multi-page font.
+    pub fn orphaned_pages(&self) -> Vec<&str> {
+        let referenced = self.referenced_pages();
+        self.pages
+            .iter()
+            .enumerate()
+            .filter(|(index, _)| u8::try_from(*index).map_or(true, |p| !referenced.contains(&p)))
+            .map(|(_, page)| page.as_str())
+            .collect()
+    }
+
+    /// Groups of glyph ids that share the exact same rect on the same page.
+    /// Glyphs with an empty rect draw nothing and are not reported.
+    pub fn find_duplicate_rects(&self) -> Vec<Vec<u32>> {
+        let mut by_rect: HashMap<(u8, Rect), Vec<u32>> = HashMap::new();
+        for ch in self.chars.values().filter(|ch| ch.area() > 0) {
+            by_rect.entry((ch.page, ch.rect())).or_default().push(ch.id);
+        }
+        let mut groups: Vec<Vec<u32>> = by_rect
+            .into_values()
+            .filter(|ids| ids.len() > 1)
+            .map(|mut ids| {
+                ids.sort_unstable();
+                ids
+            })
+            .collect();
+        groups.sort_unstable();
+        groups
+    }
+
+    /// Number of glyphs on each page, indexed by page. Covers every entry in
+    /// `pages` and any higher page a glyph refers to.
+    pub fn glyph_count_per_page(&self) -> Vec<usize> {
+        let mut counts = vec![0; self.page_slots()];
+        for ch in self.chars.values() {
+            counts[ch.page as usize] += 1;
+        }
+        counts
+    }
+
+    /// Length of a table indexed by page: every entry in `pages` and any
+    /// higher page a glyph refers to.
+    fn page_slots(&self) -> usize {
+        self.chars
+            .values()
+            .map(|ch| ch.page as usize + 1)
+            .max()
+            .unwrap_or(0)
+            .max(self.pages.len())
+    }
+
+    /// Estimated memory for all atlas pages: `pages * scale_w * scale_h *
+    /// bytes_per_pixel`. 0 without a common block.
+    pub fn atlas_bytes(&self, bytes_per_pixel: u32) -> u64 {
+        let Some(common) = &self.common else {
+            return 0;
+        };
+        self.pages.len() as u64
+            * common.scale_w as u64
+            * common.scale_h as u64
+            * bytes_per_pixel as u64
+    }
+
+    /// Fraction of the total atlas area, `pages * scale_w * scale_h`, covered
+    /// by glyph rects. Overlapping rects are counted once per glyph. 0.0
+    /// without a common block or with an empty atlas.
+    pub fn atlas_occupancy(&self) -> f32 {
+        let Some(common) = &self.common else {
+            return 0.0;
+        };
+        let total = self.pages.len() as u64 * common.scale_w as u64 * common.scale_h as u64;
+        if total == 0 {
+            return 0.0;
+        }
+        let covered: u64 = self.chars.values().map(|ch| ch.area() as u64).sum();
+        (covered as f64 / total as f64) as f32
+    }
+
+    /// Summed glyph rect area in pixels for each page, indexed by page.
+    /// Compared with `scale_w * scale_h` it gives the packing efficiency of
+    /// each page; [`BMFont::atlas_occupancy`] is the same over the whole
+    /// atlas. Covers every page in `pages` and any further page a glyph
+    /// refers to.
+    pub fn ink_area_per_page(&self) -> Vec<u64> {
+        let mut areas = vec![0; self.page_slots()];
+        for ch in self.chars.values() {
+            areas[ch.page as usize] += ch.area() as u64;
+        }
+        areas
+    }
+
+    /// Glyph whose rect on `page` contains the pixel `(x, y)`. If rects
+    /// overlap there, the glyph with the lowest id is returned.
+    pub fn glyph_at_atlas(&self, page: u8, x: u16, y: u16) -> Option<&Char> {
+        self.glyphs_on_page(page)
+            .filter(|ch| {
+                (ch.x..ch.x.saturating_add(ch.width)).contains(&x)
+                    && (ch.y..ch.y.saturating_add(ch.height)).contains(&y)
+            })
+            .min_by_key(|ch| ch.id)
+    }
+
+    /// Guesses whether the font holds signed distance field glyphs.
+    ///
+    /// SDF generators pad every glyph generously to leave room for the
+    /// distance ramp and render without supersampling, so this reports true
+    /// when the largest `info.padding` is at least 4 pixels and at least a
+    /// tenth of the median glyph height, and `aa` is at most 1. It is only a
+    /// hint; [`BMFont::is_sdf`] is the authoritative, user-set flag.
+    pub fn looks_like_sdf(&self) -> bool {
+        let Some(info) = &self.info else {
+            return false;
+        };
+        let padding = info.padding.iter().copied().max().unwrap_or(0) as u32;
+        let mut heights: Vec<u16> = self
+            .chars
+            .values()
+            .map(|ch| ch.height)
+            .filter(|h| *h > 0)
+            .collect();
+        if heights.is_empty() {
+            return false;
+        }
+        heights.sort_unstable();
+        let median = heights[heights.len() / 2] as u32;
+        info.aa <= 1 && padding >= 4 && padding * 10 >= median
+    }
+
+    /// Distance range in pixels of an SDF font, for the shader's smoothing.
+    ///
+    /// `None` unless [`BMFont::is_sdf`] is set. [`BMFont::sdf_spread_override`]
+    /// wins when present. Otherwise the spread is guessed as the largest
+    /// `info.padding`, since SDF generators pad each glyph by exactly the
+    /// distance they encode, falling back to the largest `info.spacing` for
+    /// exporters that leave the room between glyphs instead.
+    pub fn sdf_spread(&self) -> Option<f32> {
+        if !self.is_sdf {
+            return None;
+        }
+        if let Some(spread) = self.sdf_spread_override {
+            return Some(spread);
+        }
+        let info = self.info.as_ref()?;
+        let padding = info.padding.iter().copied().max().unwrap_or(0);
+        let spacing = info.spacing.iter().copied().max().unwrap_or(0);
+        [padding, spacing]
+            .into_iter()
+            .find(|value| *value > 0)
+            .map(|value| value as f32)
+    }
+
+    /// All glyphs sorted by id, for `binary_search_by_key` lookups without
+    /// hashing.
+    ///
+    /// The slice is built on first access and kept until a method of this
+    /// crate changes the glyphs. After editing `chars` directly, call
+    /// [`BMFont::invalidate_sorted_glyphs`] so the next access rebuilds it.
+    pub fn sorted_glyphs_slice(&self) -> &[Char] {
+        self.sorted_glyphs.get_or_init(|| {
+            let mut glyphs: Vec<Char> = self.chars.values().cloned().collect();
+            glyphs.sort_unstable_by_key(|ch| ch.id);
+            glyphs
+        })
+    }
+
+    /// Drops the cached [`BMFont::sorted_glyphs_slice`], to be called after
+    /// editing `chars` directly.
+    pub fn invalidate_sorted_glyphs(&mut self) {
+        self.sorted_glyphs = OnceLock::new();
+    }
+}
+
+impl Index<char> for BMFont {
+    type Output = Char;
+
+    /// Panics if the font has no glyph for `c`. Use [`BMFont::glyph`] to avoid panicking.
+    fn index(&self, c: char) -> &Char {
+        self.chars
+            .get(&(c as u32))
+            .unwrap_or_else(|| panic!("no glyph for {c:?} (U+{:04X})", c as u32))
+    }
+}
+
+impl Index<u32> for BMFont {
+    type Output = Char;
+
+    /// Panics if the font has no glyph with id `id`.
+    fn index(&self, id: u32) -> &Char {
+        self.chars
+            .get(&id)
+            .unwrap_or_else(|| panic!("no glyph with id {id} (U+{id:04X})"))
+    }
+}
@@ -0,0 +1,91 @@
+use crate::BMFont;
+use std::collections::HashSet;
+
+/// What would go wrong merging one font into another, from
+/// [`BMFont::can_merge`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MergeCompatibility {
+    /// Ids both fonts have a glyph for, sorted. The merge keeps the
+    /// receiving font's glyph.
+    pub colliding_ids: Vec<u32>,
+    /// Whether some glyph of the other font would end up on a page index
+    /// beyond `u8::MAX` once shifted behind the receiving font's pages.
+    pub page_overflow: bool,
+    /// The two `line_height`s, when both fonts have a common block and they
+    /// differ.
+    pub line_height_mismatch: Option<(u16, u16)>,
+}
+
+impl MergeCompatibility {
+    /// Whether the merge would take every glyph of the other font unchanged
+    /// into a font with uniform line metrics.
+    pub fn is_compatible(&self) -> bool {
+        self.colliding_ids.is_empty() && !self.page_overflow && self.line_height_mismatch.is_none()
+    }
+}
+
+impl BMFont {
+    /// Checks what [`BMFont::merge`] of `other` into this font would run into.
+    pub fn can_merge(&self, other: &BMFont) -> MergeCompatibility {
+        let mut colliding_ids: Vec<u32> = other
+            .chars
+            .keys()
+            .filter(|id| self.chars.contains_key(id))
+            .copied()
+            .collect();
+        colliding_ids.sort_unstable();
+        let page_offset = self.pages.len();
+        let page_overflow = other
+            .chars
+            .values()
+            .any(|ch| page_offset + ch.page as usize > u8::MAX as usize);
+        let line_height_mismatch = match (&self.common, &other.common) {
+            (Some(a), Some(b)) if a.line_height != b.line_height => {
+                Some((a.line_height, b.line_height))
+            }
+            _ => None,
+        };
+        MergeCompatibility {
+            colliding_ids,
+            page_overflow,
+            line_height_mismatch,
+        }
+    }
+
+    /// Adds the pages, glyphs and kerning of `other` to this font. Its pages
+    /// go after the existing ones and its glyphs' `page` is shifted to match.
+    /// Where both fonts have a glyph, this font's glyph and kerning are kept.
+    ///
+    /// Fails without changing anything if the shifted pages would not fit in
+    /// a `u8`; see [`BMFont::can_merge`].
+    pub fn merge(&mut self, other: &BMFont) -> Result<(), MergeCompatibility> {
+        let compatibility = self.can_merge(other);
+        if compatibility.page_overflow {
+            return Err(compatibility);
+        }
+        let page_offset = self.pages.len() as u8;
+        let colliding: HashSet<u32> = compatibility.colliding_ids.into_iter().collect();
+
+        self.pages.extend(other.pages.iter().cloned());
+        self.raw_pages.extend(other.raw_pages.iter().cloned());
+        for ch in other.chars.values() {
+            if !colliding.contains(&ch.id) {
+                let mut ch = ch.clone();
+                ch.page += page_offset;
+                self.chars.insert(ch.id, ch);
+            }
+        }
+        self.kernings.extend(
+            other
+                .kernings
+                .iter()
+                .filter(|k| !colliding.contains(&k.first) && !colliding.contains(&k.second))
+                .cloned(),
+        );
+        if let Some(common) = &mut self.common {
+            common.pages = self.pages.len() as u16;
+        }
+        self.invalidate_sorted_glyphs();
+        Ok(())
+    }
+}
@@ -0,0 +1,88 @@
+use crate::BMFont;
+use std::sync::OnceLock;
+
+/// Minimal horizontal metrics a text layout engine needs from a font.
+///
+/// Layout code can be written against this trait instead of [`BMFont`] so it
+/// stays independent of the font backend. Adapters for other font crates can
+/// implement it next to the [`BMFont`] implementation here.
+pub trait FontMetrics {
+    /// Horizontal advance of `c` in pixels, or `None` if the font lacks it.
+    fn advance(&self, c: char) -> Option<f32>;
+
+    /// Kerning adjustment in pixels applied between `first` and `second`.
+    fn kern(&self, first: char, second: char) -> f32;
+
+    /// Distance in pixels between consecutive baselines.
+    fn line_height(&self) -> f32;
+}
+
+impl FontMetrics for BMFont {
+    fn advance(&self, c: char) -> Option<f32> {
+        self.glyph(c).map(|ch| ch.x_advance as f32)
+    }
+
+    fn kern(&self, first: char, second: char) -> f32 {
+        self.kerning(first as u32, second as u32) as f32
+    }
+
+    fn line_height(&self) -> f32 {
+        self.common
+            .as_ref()
+            .map_or(0.0, |common| common.line_height as f32)
+    }
+}
+
+/// Vertical metrics of a font in pixels, shaped like the line metrics other
+/// Rust font crates report so backends can be swapped.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct LineMetrics {
+    /// Distance from the top of the line to the baseline.
+    pub ascent: f32,
+    /// Distance from the baseline to the bottom of the line, negative because
+    /// it lies below the baseline.
+    pub descent: f32,
+    /// Extra space between lines. BMFont folds it into `line_height`, so 0.
+    pub line_gap: f32,
+    /// Distance between consecutive baselines: `ascent - descent + line_gap`.
+    pub line_height: f32,
+}
+
+impl BMFont {
+    /// Line metrics from the common block: `base` is the ascent and the rest
+    /// of `line_height` the descent. `None` without a common block.
+    ///
+    /// They are derived on the first call and cached, so repeated calls are
+    /// free. Methods of this crate that change the line metrics drop the
+    /// cache; after editing `common` directly, call
+    /// [`BMFont::invalidate_line_metrics`].
+    pub fn line_metrics(&self) -> Option<LineMetrics> {
+        *self.line_metrics.get_or_init(|| {
+            let common = self.common.as_ref()?;
+            let ascent = common.base as f32;
+            let line_height = common.line_height as f32;
+            Some(LineMetrics {
+                ascent,
+                descent: ascent - line_height,
+                line_gap: 0.0,
+                line_height,
+            })
+        })
+    }
+
+    /// Drops the cached [`BMFont::line_metrics`], to be called after editing
+    /// `common` directly.
+    pub fn invalidate_line_metrics(&mut self) {
+        self.line_metrics = OnceLock::new();
+    }
+
+    /// [`LineMetrics::ascent`], or 0.0 without a common block.
+    pub fn ascent(&self) -> f32 {
+        self.line_metrics().map_or(0.0, |m| m.ascent)
+    }
+
+    /// [`LineMetrics::descent`], or 0.0 without a common block.
+    pub fn descent(&self) -> f32 {
+        self.line_metrics().map_or(0.0, |m| m.descent)
+    }
+}
@@ -0,0 +1,18 @@
+use crate::{BMFont, Char};
+use unicode_normalization::UnicodeNormalization;
+
+impl BMFont {
+    /// Glyph for a short grapheme cluster after NFC normalization, so
+    /// `"e\u{301}"` finds a font's precomposed `é`.
+    ///
+    /// Returns `None` if the cluster does not normalize to a single codepoint
+    /// or the font has no glyph for it.
+    pub fn glyph_normalized(&self, grapheme: &str) -> Option<&Char> {
+        let mut composed = grapheme.nfc();
+        let c = composed.next()?;
+        if composed.next().is_some() {
+            return None;
+        }
+        self.chars.get(&(c as u32))
+    }
+}
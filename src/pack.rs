@@ -0,0 +1,130 @@
+use crate::BMFont;
+use std::fmt;
+
+/// Why [`BMFont::reflow_to_atlas`] could not place every glyph.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum PackError {
+    /// The glyph is larger than a whole atlas page.
+    GlyphTooLarge { id: u32 },
+    /// The glyphs need more than the allowed number of pages.
+    OutOfPages { max_pages: u8 },
+}
+
+impl fmt::Display for PackError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::GlyphTooLarge { id } => write!(f, "glyph {id} does not fit on an atlas page"),
+            Self::OutOfPages { max_pages } => {
+                write!(f, "glyphs do not fit on {max_pages} atlas pages")
+            }
+        }
+    }
+}
+
+impl std::error::Error for PackError {}
+
+impl BMFont {
+    /// Repacks every glyph onto up to `max_pages` pages of `atlas_w` x
+    /// `atlas_h`, for when the atlas textures are rebuilt to match.
+    ///
+    /// Glyphs are placed tallest first in rows, left to right and top to
+    /// bottom, keeping `info.spacing` between them. `x`, `y` and `page` of
+    /// every glyph, the page names, `scale_w`/`scale_h` and `common.pages` are
+    /// updated. Existing page names, and their un-normalized `raw_pages`, are
+    /// reused in order; additional pages are named after the first one with a
+    /// `_<n>` suffix. Glyphs without pixels move to the top-left corner of
+    /// page 0. The font always keeps at least one page, so a `max_pages` of 0
+    /// fails with [`PackError::OutOfPages`] even when no glyph has pixels.
+    /// A font without a common block has nowhere to record the new atlas
+    /// size, which is then only known to the caller. On error the font is
+    /// unchanged.
+    pub fn reflow_to_atlas(
+        &mut self,
+        atlas_w: u16,
+        atlas_h: u16,
+        max_pages: u8,
+    ) -> Result<(), PackError> {
+        let (gap_x, gap_y) = self.info.as_ref().map_or((0, 0), |info| {
+            (info.spacing[0] as u32, info.spacing[1] as u32)
+        });
+        let (atlas_w, atlas_h) = (atlas_w as u32, atlas_h as u32);
+        if max_pages == 0 {
+            return Err(PackError::OutOfPages { max_pages });
+        }
+
+        let mut order: Vec<(u32, u32, u32)> = self
+            .chars
+            .values()
+            .filter(|ch| ch.area() > 0)
+            .map(|ch| (ch.id, ch.width as u32, ch.height as u32))
+            .collect();
+        order.sort_by_key(|&(id, width, height)| {
+            (std::cmp::Reverse(height), std::cmp::Reverse(width), id)
+        });
+
+        let mut placements = Vec::with_capacity(order.len());
+        let (mut page, mut x, mut y, mut row_height) = (0u32, 0, 0, 0);
+        for (id, width, height) in order {
+            if width > atlas_w || height > atlas_h {
+                return Err(PackError::GlyphTooLarge { id });
+            }
+            if x + width > atlas_w {
+                x = 0;
+                y += row_height + gap_y;
+                row_height = 0;
+            }
+            if y + height > atlas_h {
+                page += 1;
+                x = 0;
+                y = 0;
+                row_height = 0;
+            }
+            if page >= max_pages as u32 {
+                return Err(PackError::OutOfPages { max_pages });
+            }
+            placements.push((id, x as u16, y as u16, page as u8));
+            x += width + gap_x;
+            row_height = row_height.max(height);
+        }
+        let page_count = if placements.is_empty() {
+            1
+        } else {
+            page as usize + 1
+        };
+
+        for ch in self.chars.values_mut() {
+            ch.x = 0;
+            ch.y = 0;
+            ch.page = 0;
+        }
+        for (id, x, y, page) in placements {
+            let ch = self.chars.get_mut(&id).unwrap();
+            ch.x = x;
+            ch.y = y;
+            ch.page = page;
+        }
+        let pages: Vec<String> = (0..page_count)
+            .map(|n| self.reflowed_page_name(n))
+            .collect();
+        self.raw_pages = (0..page_count)
+            .map(|n| self.raw_pages.get(n).unwrap_or(&pages[n]).clone())
+            .collect();
+        self.pages = pages;
+        if let Some(common) = &mut self.common {
+            common.scale_w = atlas_w as u16;
+            common.scale_h = atlas_h as u16;
+            common.pages = page_count as u16;
+        }
+        self.invalidate_sorted_glyphs();
+        Ok(())
+    }
+
+    fn reflowed_page_name(&self, index: usize) -> String {
+        if let Some(name) = self.pages.get(index) {
+            return name.clone();
+        }
+        let first = self.pages.first().map_or("page.png", String::as_str);
+        let (stem, extension) = first.rsplit_once('.').unwrap_or((first, "png"));
+        format!("{stem}_{index}.{extension}")
+    }
+}
@@ -0,0 +1,61 @@
+use crate::BMFont;
+use std::ops::RangeInclusive;
+
+/// Writing systems [`BMFont::covers_script`] knows the letters of.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum UnicodeScript {
+    Latin,
+    Greek,
+    Cyrillic,
+    Hiragana,
+    Katakana,
+    /// The CJK Unified Ideographs block.
+    Cjk,
+    Arabic,
+    Hebrew,
+}
+
+impl UnicodeScript {
+    /// The representative codepoints of the script: its basic letters, not
+    /// the accented, historic or compatibility forms.
+    pub fn ranges(self) -> &'static [RangeInclusive<u32>] {
+        match self {
+            Self::Latin => &[0x41..=0x5A, 0x61..=0x7A],
+            Self::Greek => &[0x391..=0x3A1, 0x3A3..=0x3A9, 0x3B1..=0x3C9],
+            Self::Cyrillic => &[0x410..=0x44F],
+            Self::Hiragana => &[0x3041..=0x3096],
+            Self::Katakana => &[0x30A1..=0x30FA],
+            Self::Cjk => &[0x4E00..=0x9FFF],
+            Self::Arabic => &[0x621..=0x63A, 0x641..=0x64A],
+            Self::Hebrew => &[0x5D0..=0x5EA],
+        }
+    }
+}
+
+/// How much of a script a font has glyphs for, from
+/// [`BMFont::covers_script`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum CoverageLevel {
+    None,
+    Partial,
+    Full,
+}
+
+impl BMFont {
+    /// How many of `script`'s representative codepoints, see
+    /// [`UnicodeScript::ranges`], the font has glyphs for.
+    pub fn covers_script(&self, script: UnicodeScript) -> CoverageLevel {
+        let coverage = self.coverage_bitset();
+        let ranges = script.ranges();
+        let covered: usize = ranges.iter().map(|r| coverage.count_in(r.clone())).sum();
+        let total: usize = ranges
+            .iter()
+            .map(|r| (r.end() - r.start()) as usize + 1)
+            .sum();
+        match covered {
+            0 => CoverageLevel::None,
+            n if n == total => CoverageLevel::Full,
+            _ => CoverageLevel::Partial,
+        }
+    }
+}
@@ -0,0 +1,165 @@
+use std::io;
+
+use crate::{
+    BMFont, BmfError, Char, CommonBlock, GlyphId, GlyphTable, InfoBlock, KerningPair, ParseOptions,
+};
+
+/// A font with its glyphs in a `Vec` sorted by id instead of a `HashMap`, a
+/// compact slice looked up by binary search.
+#[derive(Debug, Clone)]
+pub struct SmallBMFont {
+    pub info: Option<InfoBlock>,
+    pub common: Option<CommonBlock>,
+    pub pages: Vec<String>,
+    /// Page names exactly as stored in the file, as [`BMFont::raw_pages`].
+    pub raw_pages: Vec<String>,
+    /// Sorted by id, one glyph per id.
+    pub chars: Vec<Char>,
+    pub kernings: Vec<KerningPair>,
+    /// See [`BMFont::unknown_blocks`].
+    pub unknown_blocks: Vec<(u8, Vec<u8>)>,
+    /// See [`BMFont::is_sdf`].
+    pub is_sdf: bool,
+    /// See [`BMFont::sdf_spread_override`].
+    pub sdf_spread_override: Option<f32>,
+    /// Amount [`SmallBMFont::kerning`] returns for pairs without an entry, as
+    /// [`BMFont::default_kerning`].
+    pub default_kerning: i16,
+}
+
+impl SmallBMFont {
+    /// Fonts with fewer glyphs than this parse into a [`SmallBMFont`] in
+    /// [`ParsedFont::from_octets`].
+    pub const MAX_GLYPHS: usize = 256;
+
+    pub fn glyph(&self, id: impl Into<GlyphId>) -> Option<&Char> {
+        let GlyphId(id) = id.into();
+        self.chars
+            .binary_search_by_key(&id, |ch| ch.id)
+            .ok()
+            .map(|index| &self.chars[index])
+    }
+
+    /// Kerning amount between two glyphs, or
+    /// [`SmallBMFont::default_kerning`] if the pair has no entry.
+    pub fn kerning(&self, first: impl Into<GlyphId>, second: impl Into<GlyphId>) -> i16 {
+        let (GlyphId(first), GlyphId(second)) = (first.into(), second.into());
+        self.kernings
+            .iter()
+            .find(|k| k.first == first && k.second == second)
+            .map_or(self.default_kerning, |k| k.amount)
+    }
+
+    /// The same font with glyphs in the `HashMap` of a [`BMFont`].
+    pub fn into_font(self) -> BMFont {
+        let mut font = BMFont {
+            info: self.info,
+            common: self.common,
+            pages: self.pages,
+            raw_pages: self.raw_pages,
+            kernings: self.kernings,
+            unknown_blocks: self.unknown_blocks,
+            is_sdf: self.is_sdf,
+            sdf_spread_override: self.sdf_spread_override,
+            default_kerning: self.default_kerning,
+            ..BMFont::new()
+        };
+        font.chars = self.chars.into_iter().map(|ch| (ch.id, ch)).collect();
+        font
+    }
+
+    /// `font` with `chars`, sorted by id, in place of its own glyphs.
+    fn with_chars(font: BMFont, chars: Vec<Char>) -> Self {
+        Self {
+            info: font.info,
+            common: font.common,
+            pages: font.pages,
+            raw_pages: font.raw_pages,
+            chars,
+            kernings: font.kernings,
+            unknown_blocks: font.unknown_blocks,
+            is_sdf: font.is_sdf,
+            sdf_spread_override: font.sdf_spread_override,
+            default_kerning: font.default_kerning,
+        }
+    }
+}
+
+impl GlyphTable for Vec<Char> {
+    fn from_records(records: &[u8], record_size: usize) -> io::Result<Self> {
+        let mut chars = records
+            .chunks(record_size)
+            .map(BMFont::parse_char_record)
+            .collect::<io::Result<Vec<Char>>>()?;
+        // Exporters write glyphs in id order, so there is usually nothing to
+        // do. Otherwise the stable sort keeps the reversed file order within
+        // an id, and the dedup keeps the last record like a `HashMap` insert.
+        if !chars.is_sorted_by(|a, b| a.id < b.id) {
+            chars.reverse();
+            chars.sort_by_key(|ch| ch.id);
+            chars.dedup_by_key(|ch| ch.id);
+        }
+        Ok(chars)
+    }
+
+    fn len(&self) -> usize {
+        Vec::len(self)
+    }
+}
+
+impl From<BMFont> for SmallBMFont {
+    fn from(mut font: BMFont) -> Self {
+        let mut chars: Vec<Char> = std::mem::take(&mut font.chars).into_values().collect();
+        chars.sort_unstable_by_key(|ch| ch.id);
+        Self::with_chars(font, chars)
+    }
+}
+
+/// A binary font in whichever representation suits its size.
+#[derive(Debug, Clone)]
+pub enum ParsedFont {
+    Small(SmallBMFont),
+    Large(BMFont),
+}
+
+impl ParsedFont {
+    /// Parses `data` like [`BMFont::from_octets`], accepting exactly the same
+    /// inputs. The glyphs are read into a sorted `Vec` first; a font with
+    /// fewer than [`SmallBMFont::MAX_GLYPHS`] of them stays a
+    /// [`SmallBMFont`] and never builds a `HashMap`, a larger one becomes a
+    /// [`BMFont`].
+    pub fn from_octets(data: &[u8]) -> Result<Self, BmfError> {
+        let (font, chars, _) = BMFont::parse_blocks::<Vec<Char>>(
+            data,
+            &ParseOptions::default(),
+            false,
+            &mut Vec::new(),
+        )?;
+        if chars.len() >= SmallBMFont::MAX_GLYPHS {
+            let mut font = font;
+            font.chars = chars.into_iter().map(|ch| (ch.id, ch)).collect();
+            Ok(Self::Large(font))
+        } else {
+            Ok(Self::Small(SmallBMFont::with_chars(font, chars)))
+        }
+    }
+
+    /// Whether the font was stored as a [`SmallBMFont`].
+    pub fn is_small(&self) -> bool {
+        matches!(self, Self::Small(_))
+    }
+
+    pub fn glyph(&self, id: impl Into<GlyphId>) -> Option<&Char> {
+        match self {
+            Self::Small(font) => font.glyph(id),
+            Self::Large(font) => font.glyph(id),
+        }
+    }
+
+    pub fn kerning(&self, first: impl Into<GlyphId>, second: impl Into<GlyphId>) -> i16 {
+        match self {
+            Self::Small(font) => font.kerning(first, second),
+            Self::Large(font) => font.kerning(first, second),
+        }
+    }
+}
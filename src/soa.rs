@@ -0,0 +1,64 @@
+use crate::{BMFont, Char};
+
+/// The glyphs of a font as parallel arrays, one per [`Char`] field, for
+/// uploading to GPU storage buffers.
+///
+/// Every `Vec` has the same length, and index `i` of each describes the same
+/// glyph. Glyphs are in ascending `id` order without duplicates, so a shader
+/// finds a codepoint by binary search over `ids`. Each array keeps the
+/// field's own type, tightly packed; widening to the 4-byte elements std430
+/// layouts expect for `u16`, `i16` and `u8` is left to the upload code.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct GlyphSoa {
+    pub ids: Vec<u32>,
+    pub x: Vec<u16>,
+    pub y: Vec<u16>,
+    pub width: Vec<u16>,
+    pub height: Vec<u16>,
+    pub x_offset: Vec<i16>,
+    pub y_offset: Vec<i16>,
+    pub x_advance: Vec<i16>,
+    pub page: Vec<u8>,
+    pub chnl: Vec<u8>,
+}
+
+impl GlyphSoa {
+    pub fn len(&self) -> usize {
+        self.ids.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.ids.is_empty()
+    }
+
+    /// Index of the glyph with `id` in every array, the lookup a shader does.
+    pub fn index_of(&self, id: u32) -> Option<usize> {
+        self.ids.binary_search(&id).ok()
+    }
+
+    fn push(&mut self, ch: &Char) {
+        self.ids.push(ch.id);
+        self.x.push(ch.x);
+        self.y.push(ch.y);
+        self.width.push(ch.width);
+        self.height.push(ch.height);
+        self.x_offset.push(ch.x_offset);
+        self.y_offset.push(ch.y_offset);
+        self.x_advance.push(ch.x_advance);
+        self.page.push(ch.page);
+        self.chnl.push(ch.chnl);
+    }
+}
+
+impl BMFont {
+    /// The glyphs in structure-of-arrays layout, sorted by id.
+    pub fn to_soa(&self) -> GlyphSoa {
+        let mut chars: Vec<&Char> = self.chars.values().collect();
+        chars.sort_unstable_by_key(|ch| ch.id);
+        let mut soa = GlyphSoa::default();
+        for ch in chars {
+            soa.push(ch);
+        }
+        soa
+    }
+}
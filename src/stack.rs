@@ -0,0 +1,51 @@
+use crate::{BMFont, Char, CoverageSet, GlyphId};
+use std::collections::HashSet;
+
+/// Fonts tried in order when looking up a glyph, so characters missing from
+/// the primary font fall back to the next font that has them.
+#[derive(Debug, Clone, Default)]
+pub struct FontStack {
+    pub fonts: Vec<BMFont>,
+}
+
+impl FontStack {
+    pub fn new(fonts: Vec<BMFont>) -> Self {
+        Self { fonts }
+    }
+
+    /// Appends a fallback font, tried after all fonts already in the stack.
+    pub fn push(&mut self, font: BMFont) {
+        self.fonts.push(font);
+    }
+
+    /// The first font that has a glyph for `id`, as its index in the stack,
+    /// together with the glyph.
+    pub fn glyph(&self, id: impl Into<GlyphId>) -> Option<(usize, &Char)> {
+        let id = id.into();
+        self.fonts
+            .iter()
+            .enumerate()
+            .find_map(|(index, font)| font.glyph(id).map(|ch| (index, ch)))
+    }
+
+    /// Every codepoint at least one font in the stack has a glyph for.
+    pub fn combined_coverage(&self) -> CoverageSet {
+        CoverageSet::from_ids(
+            self.fonts
+                .iter()
+                .flat_map(|font| font.chars.keys().copied()),
+        )
+    }
+
+    /// Characters of `text` that no font in the stack can render, each listed
+    /// once in order of first appearance. Line breaks are layout, not glyphs,
+    /// and are never reported.
+    pub fn uncovered(&self, text: &str) -> Vec<char> {
+        let mut seen = HashSet::new();
+        text.chars()
+            .filter(|c| !matches!(c, '\n' | '\r'))
+            .filter(|c| self.glyph(*c).is_none())
+            .filter(|c| seen.insert(*c))
+            .collect()
+    }
+}
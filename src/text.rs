@@ -0,0 +1,502 @@
+use crate::{BMFont, BmfError, Char, CommonBlock, InfoBlock, KerningPair};
+use std::borrow::Cow;
+#[cfg(feature = "extra-attributes")]
+use std::collections::BTreeMap;
+use std::collections::HashMap;
+use std::fmt::Write;
+use std::str::FromStr;
+
+/// Windows charset ids as BMFont names them in the text format.
+pub(crate) const CHARSETS: [(u8, &str); 19] = [
+    (0, "ANSI"),
+    (1, "DEFAULT"),
+    (2, "SYMBOL"),
+    (77, "MAC"),
+    (128, "SHIFTJIS"),
+    (129, "HANGUL"),
+    (130, "JOHAB"),
+    (134, "GB2312"),
+    (136, "CHINESEBIG5"),
+    (161, "GREEK"),
+    (162, "TURKISH"),
+    (163, "VIETNAMESE"),
+    (177, "HEBREW"),
+    (178, "ARABIC"),
+    (186, "BALTIC"),
+    (204, "RUSSIAN"),
+    (222, "THAI"),
+    (238, "EASTEUROPE"),
+    (255, "OEM"),
+];
+
+impl Char {
+    /// The glyph as a `char` line of the text format, attributes in spec order.
+    pub fn to_text_line(&self) -> String {
+        #[allow(unused_mut)]
+        let mut line = format!(
+            "char id={} x={} y={} width={} height={} xoffset={} yoffset={} xadvance={} page={} chnl={}",
+            self.id,
+            self.x,
+            self.y,
+            self.width,
+            self.height,
+            self.x_offset,
+            self.y_offset,
+            self.x_advance,
+            self.page,
+            self.chnl
+        );
+        #[cfg(feature = "extra-attributes")]
+        push_extra(&mut line, &self.extra);
+        line
+    }
+}
+
+/// `value` ready to go between the double quotes of a text-format attribute.
+/// The format has no escapes, so a `"` inside it becomes `'`.
+fn quotable(value: &str) -> Cow<'_, str> {
+    if value.contains('"') {
+        Cow::Owned(value.replace('"', "'"))
+    } else {
+        Cow::Borrowed(value)
+    }
+}
+
+/// Appends `extra` attributes to a text line, quoting values with spaces.
+#[cfg(feature = "extra-attributes")]
+fn push_extra(line: &mut String, extra: &BTreeMap<String, String>) {
+    for (key, value) in extra {
+        let value = quotable(value);
+        if value.is_empty() || value.contains(char::is_whitespace) {
+            write!(line, " {key}=\"{value}\"").unwrap();
+        } else {
+            write!(line, " {key}={value}").unwrap();
+        }
+    }
+}
+
+impl KerningPair {
+    pub fn to_text_line(&self) -> String {
+        format!(
+            "kerning first={} second={} amount={}",
+            self.first, self.second, self.amount
+        )
+    }
+}
+
+impl InfoBlock {
+    /// The `charset` attribute: empty for a Unicode font, otherwise the
+    /// charset's name, or its number if it has none.
+    pub(crate) fn charset_name(&self) -> String {
+        if self.unicode() {
+            String::new()
+        } else {
+            CHARSETS
+                .iter()
+                .find(|(id, _)| *id == self.char_set)
+                .map_or_else(|| self.char_set.to_string(), |(_, name)| name.to_string())
+        }
+    }
+
+    pub fn to_text_line(&self) -> String {
+        let charset = self.charset_name();
+        #[allow(unused_mut)]
+        let mut line = format!(
+            "info face=\"{}\" size={} bold={} italic={} charset=\"{}\" unicode={} stretchH={} smooth={} aa={} padding={},{},{},{} spacing={},{} outline={}",
+            quotable(&self.font_name),
+            self.font_size,
+            self.bold() as u8,
+            self.italic() as u8,
+            charset,
+            self.unicode() as u8,
+            self.stretch_h,
+            self.smooth() as u8,
+            self.aa,
+            self.padding[0],
+            self.padding[1],
+            self.padding[2],
+            self.padding[3],
+            self.spacing[0],
+            self.spacing[1],
+            self.outline
+        );
+        #[cfg(feature = "extra-attributes")]
+        push_extra(&mut line, &self.extra);
+        line
+    }
+}
+
+impl CommonBlock {
+    pub fn to_text_line(&self) -> String {
+        format!(
+            "common lineHeight={} base={} scaleW={} scaleH={} pages={} packed={} alphaChnl={} redChnl={} greenChnl={} blueChnl={}",
+            self.line_height,
+            self.base,
+            self.scale_w,
+            self.scale_h,
+            self.pages,
+            self.packed() as u8,
+            self.alpha_chnl,
+            self.red_chnl,
+            self.green_chnl,
+            self.blue_chnl
+        )
+    }
+}
+
+impl BMFont {
+    /// The font in the BMFont text format. Glyphs are written in id order.
+    ///
+    /// The format cannot escape a `"` in a quoted value, so the face name and
+    /// page file names are written with any `"` replaced by `'`; only names
+    /// without one round-trip exactly through [`BMFont::from_text`].
+    pub fn to_text(&self) -> String {
+        let mut text = String::new();
+        if let Some(info) = &self.info {
+            writeln!(text, "{}", info.to_text_line()).unwrap();
+        }
+        if let Some(common) = &self.common {
+            writeln!(text, "{}", common.to_text_line()).unwrap();
+        }
+        for (id, page) in self.pages.iter().enumerate() {
+            writeln!(text, "page id={id} file=\"{}\"", quotable(page)).unwrap();
+        }
+
+        let mut chars: Vec<&Char> = self.chars.values().collect();
+        chars.sort_by_key(|ch| ch.id);
+        writeln!(text, "chars count={}", chars.len()).unwrap();
+        for ch in chars {
+            writeln!(text, "{}", ch.to_text_line()).unwrap();
+        }
+
+        if !self.kernings.is_empty() {
+            writeln!(text, "kernings count={}", self.kernings.len()).unwrap();
+            for kerning in &self.kernings {
+                writeln!(text, "{}", kerning.to_text_line()).unwrap();
+            }
+        }
+        text
+    }
+
+    /// Multi-line, human-readable summary of one glyph for debugging: its
+    /// codepoint, rect, offsets, advance, page file, channel and the kerning
+    /// pairs it takes part in. `None` if the font has no glyph for `c`.
+    pub fn describe_glyph(&self, c: char) -> Option<String> {
+        let ch = self.glyph(c)?;
+        let mut text = String::new();
+        writeln!(text, "U+{:04X} {c:?}", ch.id).unwrap();
+        writeln!(
+            text,
+            "rect: x={} y={} width={} height={}",
+            ch.x, ch.y, ch.width, ch.height
+        )
+        .unwrap();
+        writeln!(text, "offset: x={} y={}", ch.x_offset, ch.y_offset).unwrap();
+        writeln!(text, "advance: {}", ch.x_advance).unwrap();
+        let file = self.page_file(ch.page).unwrap_or("<missing>");
+        writeln!(text, "page: {} ({file})", ch.page).unwrap();
+        writeln!(text, "channel: {}", ch.chnl).unwrap();
+        let name = |id: u32| {
+            char::from_u32(id).map_or_else(|| format!("U+{id:04X}"), |c| format!("{c:?}"))
+        };
+        for k in self
+            .kernings
+            .iter()
+            .filter(|k| k.first == ch.id || k.second == ch.id)
+        {
+            writeln!(
+                text,
+                "kerning: {} {} {}",
+                name(k.first),
+                name(k.second),
+                k.amount
+            )
+            .unwrap();
+        }
+        Some(text)
+    }
+}
+
+/// A `key=value` attribute of a text line or XML element.
+pub(crate) type Attribute<'a> = (&'a str, Cow<'a, str>);
+
+pub(crate) type Attributes<'a> = [Attribute<'a>];
+
+/// Assembles a font from the tags and attributes shared by the text and XML
+/// formats. Missing attributes default to 0 or empty, which is how exporters
+/// such as libGDX's Hiero get away with leaving some of them out.
+#[derive(Default)]
+pub(crate) struct FontBuilder {
+    info: Option<InfoBlock>,
+    common: Option<CommonBlock>,
+    pages: Vec<String>,
+    chars: HashMap<u32, Char>,
+    kernings: Vec<KerningPair>,
+}
+
+impl FontBuilder {
+    /// Applies one tag. Returns `Ok(false)` for a tag it does not know.
+    pub(crate) fn apply(
+        &mut self,
+        tag: &str,
+        attributes: &Attributes,
+        line: usize,
+    ) -> Result<bool, BmfError> {
+        let attrs = Attrs { attributes, line };
+        match tag {
+            "info" => {
+                let mut bit_field = 0;
+                for (key, bit) in [
+                    ("smooth", 0b1000_0000),
+                    ("unicode", 0b0100_0000),
+                    ("italic", 0b0010_0000),
+                    ("bold", 0b0001_0000),
+                    ("fixedHeight", 0b0000_1000),
+                ] {
+                    if attrs.number::<u8>(key)? != 0 {
+                        bit_field |= bit;
+                    }
+                }
+                let charset = attrs.get("charset").unwrap_or("");
+                let char_set = match CHARSETS.iter().find(|(_, name)| *name == charset) {
+                    Some((id, _)) => *id,
+                    None if charset.is_empty() => 0,
+                    None => charset.parse().map_err(|_| attrs.invalid("charset"))?,
+                };
+                let padding = attrs.list::<4>("padding")?;
+                let spacing = attrs.list::<2>("spacing")?;
+                self.info = Some(InfoBlock {
+                    font_size: attrs.number("size")?,
+                    bit_field,
+                    char_set,
+                    stretch_h: attrs.number_or("stretchH", 100)?,
+                    aa: attrs.number("aa")?,
+                    padding,
+                    spacing,
+                    outline: attrs.number("outline")?,
+                    font_name: attrs.get("face").unwrap_or("").to_string(),
+                    #[cfg(feature = "extra-attributes")]
+                    extra: attrs.extra(&[
+                        "face",
+                        "size",
+                        "bold",
+                        "italic",
+                        "charset",
+                        "unicode",
+                        "stretchH",
+                        "smooth",
+                        "aa",
+                        "padding",
+                        "spacing",
+                        "outline",
+                        "fixedHeight",
+                    ]),
+                });
+            }
+            "common" => {
+                self.common = Some(CommonBlock {
+                    line_height: attrs.number("lineHeight")?,
+                    base: attrs.number("base")?,
+                    scale_w: attrs.number("scaleW")?,
+                    scale_h: attrs.number("scaleH")?,
+                    pages: attrs.number("pages")?,
+                    bit_field: attrs.number::<u8>("packed")? & 1,
+                    alpha_chnl: attrs.number("alphaChnl")?,
+                    red_chnl: attrs.number("redChnl")?,
+                    green_chnl: attrs.number("greenChnl")?,
+                    blue_chnl: attrs.number("blueChnl")?,
+                });
+            }
+            "page" => {
+                let id: u8 = attrs.number("id")?;
+                let index = id as usize;
+                if self.pages.len() <= index {
+                    self.pages.resize(index + 1, String::new());
+                }
+                self.pages[index] = attrs.get("file").unwrap_or("").to_string();
+            }
+            "char" => {
+                let ch = Char {
+                    id: attrs.number("id")?,
+                    x: attrs.number("x")?,
+                    y: attrs.number("y")?,
+                    width: attrs.number("width")?,
+                    height: attrs.number("height")?,
+                    x_offset: attrs.number("xoffset")?,
+                    y_offset: attrs.number("yoffset")?,
+                    x_advance: attrs.number("xadvance")?,
+                    page: attrs.number("page")?,
+                    chnl: attrs.number("chnl")?,
+                    #[cfg(feature = "extra-attributes")]
+                    extra: attrs.extra(&[
+                        "id", "x", "y", "width", "height", "xoffset", "yoffset", "xadvance",
+                        "page", "chnl",
+                    ]),
+                };
+                self.chars.insert(ch.id, ch);
+            }
+            "kerning" => self.kernings.push(KerningPair {
+                first: attrs.number("first")?,
+                second: attrs.number("second")?,
+                amount: attrs.number("amount")?,
+            }),
+            // Counts only announce what follows; the records themselves are authoritative.
+            "chars" | "kernings" => (),
+            _ => return Ok(false),
+        }
+        Ok(true)
+    }
+
+    pub(crate) fn finish(self) -> BMFont {
+        BMFont {
+            info: self.info,
+            common: self.common,
+            raw_pages: self.pages.clone(),
+            pages: self.pages,
+            chars: self.chars,
+            kernings: self.kernings,
+            ..BMFont::new()
+        }
+    }
+}
+
+struct Attrs<'a, 'b> {
+    attributes: &'b Attributes<'a>,
+    line: usize,
+}
+
+impl Attrs<'_, '_> {
+    fn get(&self, key: &str) -> Option<&str> {
+        self.attributes
+            .iter()
+            .find(|(k, _)| *k == key)
+            .map(|(_, v)| v.as_ref())
+    }
+
+    /// The attributes whose key is not in `known`.
+    #[cfg(feature = "extra-attributes")]
+    fn extra(&self, known: &[&str]) -> BTreeMap<String, String> {
+        self.attributes
+            .iter()
+            .filter(|(key, _)| !known.contains(key))
+            .map(|(key, value)| (key.to_string(), value.to_string()))
+            .collect()
+    }
+
+    fn invalid(&self, key: &str) -> BmfError {
+        let value = self.get(key).unwrap_or("").trim();
+        let message = if value.parse::<i128>().is_ok() {
+            // An integer that does not fit the field, such as a kerning amount
+            // beyond the i16 the binary format stores.
+            format!("value {value} for {key} is out of range")
+        } else {
+            format!("invalid value for {key}: {value:?}")
+        };
+        BmfError::Syntax {
+            line: self.line,
+            message,
+        }
+    }
+
+    fn number<T: FromStr + Default>(&self, key: &str) -> Result<T, BmfError> {
+        self.number_or(key, T::default())
+    }
+
+    fn number_or<T: FromStr>(&self, key: &str, default: T) -> Result<T, BmfError> {
+        match self.get(key) {
+            None => Ok(default),
+            Some(value) => value.trim().parse().map_err(|_| self.invalid(key)),
+        }
+    }
+
+    /// Comma-separated list such as `padding=1,1,1,1`. Missing entries are 0,
+    /// and negative entries (Hiero writes `spacing=-2,-2`) are clamped to 0.
+    fn list<const N: usize>(&self, key: &str) -> Result<[u8; N], BmfError> {
+        let mut values = [0; N];
+        if let Some(list) = self.get(key) {
+            for (value, item) in values.iter_mut().zip(list.split(',')) {
+                let item: i32 = item.trim().parse().map_err(|_| self.invalid(key))?;
+                *value = item.clamp(0, u8::MAX as i32) as u8;
+            }
+        }
+        Ok(values)
+    }
+}
+
+impl BMFont {
+    /// Parses a font in the BMFont text format.
+    pub fn from_text(text: &str) -> Result<Self, BmfError> {
+        let text = text.strip_prefix('\u{FEFF}').unwrap_or(text);
+        let mut builder = FontBuilder::default();
+        for (index, line) in text.lines().enumerate() {
+            let line_number = index + 1;
+            let Some((tag, attributes)) = tokenize_line(line, line_number)? else {
+                continue;
+            };
+            if !builder.apply(tag, &attributes, line_number)? {
+                return Err(BmfError::Syntax {
+                    line: line_number,
+                    message: format!("unknown tag {tag:?}"),
+                });
+            }
+        }
+        Ok(builder.finish())
+    }
+}
+
+/// Splits a text-format line into its tag and attributes. Attributes are
+/// separated by runs of whitespace, spaces and tabs alike, which may also
+/// surround the `=`; values may be quoted to contain whitespace.
+/// Returns `None` for a blank line or a `#` or `//` comment.
+fn tokenize_line(
+    line: &str,
+    line_number: usize,
+) -> Result<Option<(&str, Vec<Attribute<'_>>)>, BmfError> {
+    let mut rest = line.trim_start();
+    if rest.starts_with('#') || rest.starts_with("//") {
+        return Ok(None);
+    }
+    let tag_end = rest.find(char::is_whitespace).unwrap_or(rest.len());
+    let tag = &rest[..tag_end];
+    if tag.is_empty() {
+        return Ok(None);
+    }
+    rest = &rest[tag_end..];
+
+    let mut attributes = Vec::new();
+    loop {
+        rest = rest.trim_start();
+        if rest.is_empty() {
+            break;
+        }
+        let Some(eq) = rest.find('=') else {
+            return Err(BmfError::Syntax {
+                line: line_number,
+                message: format!("expected key=value, found {rest:?}"),
+            });
+        };
+        let key = rest[..eq].trim_end();
+        if key.is_empty() || key.contains(char::is_whitespace) {
+            return Err(BmfError::Syntax {
+                line: line_number,
+                message: format!("expected key=value, found {rest:?}"),
+            });
+        }
+        rest = rest[eq + 1..].trim_start();
+        let value = if let Some(quoted) = rest.strip_prefix('"') {
+            let end = quoted.find('"').ok_or_else(|| BmfError::Syntax {
+                line: line_number,
+                message: format!("unterminated quote in {key}"),
+            })?;
+            rest = &quoted[end + 1..];
+            &quoted[..end]
+        } else {
+            let end = rest.find(char::is_whitespace).unwrap_or(rest.len());
+            let value = &rest[..end];
+            rest = &rest[end..];
+            value
+        };
+        attributes.push((key, Cow::Borrowed(value)));
+    }
+    Ok(Some((tag, attributes)))
+}
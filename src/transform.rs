@@ -0,0 +1,91 @@
+use crate::BMFont;
+
+impl BMFont {
+    /// Copy of the font with every pixel quantity multiplied by `factor`, as if
+    /// the atlas textures had been resized by the same factor.
+    /// See [`BMFont::rescale_metrics`] for the rounding rule.
+    pub fn scaled(&self, factor: f32) -> BMFont {
+        let mut font = self.clone();
+        font.rescale_metrics(factor);
+        font
+    }
+
+    /// Copy of the font scaled by exactly `numerator / denominator`, computed
+    /// in integers so that ratios such as 2/1 or 3/2 give pixel-exact results
+    /// without float drift. Rounds half to even like [`BMFont::scaled`], into
+    /// the integer fields, so a ratio with a remainder still leaves every
+    /// glyph rect and offset on the pixel grid.
+    ///
+    /// # Panics
+    ///
+    /// If `denominator` is zero.
+    pub fn scaled_ratio(&self, numerator: u32, denominator: u32) -> BMFont {
+        assert!(denominator != 0, "scaled_ratio with a zero denominator");
+        let (numerator, denominator) = (numerator as i64, denominator as i64);
+        let mut font = self.clone();
+        font.rescale_with(|value| {
+            let product = value * numerator;
+            let quotient = product.div_euclid(denominator);
+            let remainder = product.rem_euclid(denominator) * 2;
+            if remainder > denominator || (remainder == denominator && quotient % 2 != 0) {
+                quotient + 1
+            } else {
+                quotient
+            }
+        });
+        font
+    }
+
+    /// Multiplies every pixel quantity in place by `factor`: line metrics,
+    /// atlas size, glyph rects, offsets and advances, kerning amounts and the
+    /// info block's size, padding, spacing and outline.
+    ///
+    /// Each value is rounded half to even, one rule for all of them so that
+    /// related metrics do not drift apart, and `base` never ends up above
+    /// `line_height` if it was not before. The results are stored in the
+    /// integer fields, so glyphs stay on whole pixels and need no snapping.
+    pub fn rescale_metrics(&mut self, factor: f32) {
+        self.rescale_with(|value| (value as f32 * factor).round_ties_even() as i64);
+    }
+
+    /// Applies `scale` to every pixel quantity, clamping each result to the
+    /// range of its field.
+    fn rescale_with(&mut self, scale: impl Fn(i64) -> i64) {
+        let scale_u8 = |value: u8| scale(value as i64).clamp(0, u8::MAX as i64) as u8;
+        let scale_u16 = |value: u16| scale(value as i64).clamp(0, u16::MAX as i64) as u16;
+        let scale_i16 =
+            |value: i16| scale(value as i64).clamp(i16::MIN as i64, i16::MAX as i64) as i16;
+
+        if let Some(info) = &mut self.info {
+            info.font_size = scale_i16(info.font_size);
+            for value in info.padding.iter_mut().chain(info.spacing.iter_mut()) {
+                *value = scale_u8(*value);
+            }
+            info.outline = scale_u8(info.outline);
+        }
+        if let Some(common) = &mut self.common {
+            let base_fits = common.base <= common.line_height;
+            common.line_height = scale_u16(common.line_height);
+            common.base = scale_u16(common.base);
+            if base_fits {
+                common.base = common.base.min(common.line_height);
+            }
+            common.scale_w = scale_u16(common.scale_w);
+            common.scale_h = scale_u16(common.scale_h);
+        }
+        for ch in self.chars.values_mut() {
+            ch.x = scale_u16(ch.x);
+            ch.y = scale_u16(ch.y);
+            ch.width = scale_u16(ch.width);
+            ch.height = scale_u16(ch.height);
+            ch.x_offset = scale_i16(ch.x_offset);
+            ch.y_offset = scale_i16(ch.y_offset);
+            ch.x_advance = scale_i16(ch.x_advance);
+        }
+        for kerning in &mut self.kernings {
+            kerning.amount = scale_i16(kerning.amount);
+        }
+        self.invalidate_sorted_glyphs();
+        self.invalidate_line_metrics();
+    }
+}
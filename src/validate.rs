@@ -0,0 +1,143 @@
+use crate::{BMFont, Char};
+
+/// A consistency problem found by [`BMFont::validate`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ValidationIssue {
+    /// The glyph's rect extends past `scale_w`/`scale_h`.
+    RectOutOfBounds { id: u32 },
+    /// The glyph's `page` is not below the count declared in `common.pages`.
+    PageBeyondDeclaredCount { id: u32, page: u8 },
+    /// The glyph's `page` has no entry in the pages list.
+    PageBeyondPageList { id: u32, page: u8 },
+    /// The glyph's offsets are too far out to be genuine; see
+    /// [`BMFont::implausible_offsets`].
+    ImplausibleOffset { id: u32 },
+}
+
+impl BMFont {
+    /// Runs every consistency check and returns the problems found, ordered by
+    /// check and then by glyph id.
+    pub fn validate(&self) -> Vec<ValidationIssue> {
+        let mut issues: Vec<ValidationIssue> = self
+            .rects_in_bounds()
+            .into_iter()
+            .map(|id| ValidationIssue::RectOutOfBounds { id })
+            .collect();
+
+        let mut chars: Vec<&Char> = self.chars.values().collect();
+        chars.sort_by_key(|ch| ch.id);
+        if let Some(common) = &self.common {
+            issues.extend(
+                chars
+                    .iter()
+                    .filter(|ch| ch.page as u16 >= common.pages)
+                    .map(|ch| ValidationIssue::PageBeyondDeclaredCount {
+                        id: ch.id,
+                        page: ch.page,
+                    }),
+            );
+        }
+        issues.extend(
+            chars
+                .iter()
+                .filter(|ch| ch.page as usize >= self.pages.len())
+                .map(|ch| ValidationIssue::PageBeyondPageList {
+                    id: ch.id,
+                    page: ch.page,
+                }),
+        );
+        issues.extend(
+            self.implausible_offsets()
+                .into_iter()
+                .map(|id| ValidationIssue::ImplausibleOffset { id }),
+        );
+        issues
+    }
+
+    /// Ids of glyphs whose offsets suggest a corrupt or misaligned record,
+    /// sorted: an `x_offset` or `y_offset` more than twice the font's size
+    /// away from the pen, or a glyph reaching more than the font's size past
+    /// its own advance. The size is the larger of `line_height` and
+    /// `info.font_size`, at least 1.
+    pub fn implausible_offsets(&self) -> Vec<u32> {
+        let line_height = self.common.as_ref().map_or(0, |c| c.line_height as i32);
+        let font_size = self.info.as_ref().map_or(0, |i| i.requested_size() as i32);
+        let size = line_height.max(font_size).max(1);
+        let mut ids: Vec<u32> = self
+            .chars
+            .values()
+            .filter(|ch| {
+                let (x_offset, y_offset) = (ch.x_offset as i32, ch.y_offset as i32);
+                x_offset.abs() > 2 * size
+                    || y_offset.abs() > 2 * size
+                    || x_offset + ch.width as i32 > (ch.x_advance as i32).max(0) + size
+            })
+            .map(|ch| ch.id)
+            .collect();
+        ids.sort_unstable();
+        ids
+    }
+
+    /// Ids of glyphs whose rect does not fit within the `scale_w` x `scale_h`
+    /// atlas, sorted. Empty if the font has no common block.
+    pub fn rects_in_bounds(&self) -> Vec<u32> {
+        let Some(common) = &self.common else {
+            return Vec::new();
+        };
+        let mut ids: Vec<u32> = self
+            .chars
+            .values()
+            .filter(|ch| {
+                ch.x as u32 + ch.width as u32 > common.scale_w as u32
+                    || ch.y as u32 + ch.height as u32 > common.scale_h as u32
+            })
+            .map(|ch| ch.id)
+            .collect();
+        ids.sort_unstable();
+        ids
+    }
+
+    /// Checks that every glyph on `page` fits in the texture actually loaded
+    /// for it, whose size may differ from `scale_w`/`scale_h` when the `.fnt`
+    /// and the image do not match. Returns the ids that do not fit, sorted.
+    pub fn verify_against_texture(&self, page: u8, tex_w: u32, tex_h: u32) -> Result<(), Vec<u32>> {
+        let mut ids: Vec<u32> = self
+            .chars
+            .values()
+            .filter(|ch| ch.page == page)
+            .filter(|ch| {
+                ch.x as u32 + ch.width as u32 > tex_w || ch.y as u32 + ch.height as u32 > tex_h
+            })
+            .map(|ch| ch.id)
+            .collect();
+        if ids.is_empty() {
+            return Ok(());
+        }
+        ids.sort_unstable();
+        Err(ids)
+    }
+
+    /// Shrinks the `width`/`height` of glyphs whose rect runs past the atlas
+    /// so that sampling stays inside the texture. A glyph that starts outside
+    /// the atlas is left with an empty rect. Returns how many glyphs changed.
+    pub fn clamp_rects_to_atlas(&mut self) -> usize {
+        let Some(common) = &self.common else {
+            return 0;
+        };
+        let (scale_w, scale_h) = (common.scale_w, common.scale_h);
+        let mut adjusted = 0;
+        for ch in self.chars.values_mut() {
+            let width = ch.width.min(scale_w.saturating_sub(ch.x));
+            let height = ch.height.min(scale_h.saturating_sub(ch.y));
+            if (width, height) != (ch.width, ch.height) {
+                ch.width = width;
+                ch.height = height;
+                adjusted += 1;
+            }
+        }
+        if adjusted > 0 {
+            self.invalidate_sorted_glyphs();
+        }
+        adjusted
+    }
+}
@@ -0,0 +1,59 @@
+use crate::BmfError;
+use std::fmt;
+
+/// A non-fatal problem noticed by [`BMFont::from_octets_lenient`](crate::BMFont::from_octets_lenient).
+#[derive(Debug)]
+pub enum ParseWarning {
+    /// A block of a type this crate does not know was kept as is in
+    /// [`BMFont::unknown_blocks`](crate::BMFont::unknown_blocks).
+    UnknownBlock { block: u8 },
+    /// A name in the block was not UTF-8 and was decoded with replacement characters.
+    InvalidUtf8 { block: u8 },
+    /// The block could not be parsed and was skipped.
+    InvalidBlock { block: u8, message: String },
+    /// The chars block holds several records and nothing but zero bytes, which
+    /// almost always means corruption or a misparsed offset.
+    ZeroedCharsBlock { records: usize },
+    /// Several char records share an id; only the last of each was kept.
+    DuplicateGlyphIds { records: usize, distinct: usize },
+    /// The data ended inside a block; only its complete records were kept.
+    Truncated,
+    /// `common.pages` disagrees with the number of names in the pages block.
+    PageCountMismatch { declared: u16, actual: usize },
+    /// A glyph refers to a page that has no name in the pages block.
+    PageOutOfRange { id: u32, page: u8 },
+    /// Parsing gave up; no font was produced.
+    Failed(BmfError),
+}
+
+impl fmt::Display for ParseWarning {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::UnknownBlock { block } => write!(f, "kept unknown block {block} as is"),
+            Self::InvalidUtf8 { block } => write!(f, "block {block} has names that are not UTF-8"),
+            Self::InvalidBlock { block, message } => {
+                write!(f, "skipped block {block}: {message}")
+            }
+            Self::ZeroedCharsBlock { records } => {
+                write!(f, "chars block of {records} records is all zeros")
+            }
+            Self::DuplicateGlyphIds { records, distinct } => {
+                write!(
+                    f,
+                    "{records} char records have only {distinct} distinct ids"
+                )
+            }
+            Self::Truncated => write!(f, "data ends inside a block"),
+            Self::PageCountMismatch { declared, actual } => {
+                write!(
+                    f,
+                    "common block declares {declared} pages but {actual} are named"
+                )
+            }
+            Self::PageOutOfRange { id, page } => {
+                write!(f, "glyph {id} refers to missing page {page}")
+            }
+            Self::Failed(e) => write!(f, "{e}"),
+        }
+    }
+}
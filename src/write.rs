@@ -0,0 +1,167 @@
+use crate::{BMFont, Char, BMF_MAGIC, BMF_VERSION, KERNING_RECORD_SIZE};
+use byteorder::{LittleEndian, WriteBytesExt};
+use std::io::{self, Write};
+
+const INFO_FIXED_SIZE: usize = 14;
+const COMMON_SIZE: usize = 15;
+const BLOCK_HEADER_SIZE: usize = 5;
+
+/// Body sizes, in bytes, of the blocks [`BMFont::write_to`] emits, each
+/// `None` when the block is left out. Every present block also takes a
+/// five-byte header.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BlockSizes {
+    pub info: Option<usize>,
+    pub common: Option<usize>,
+    pub pages: usize,
+    pub chars: usize,
+    pub kerning: Option<usize>,
+    /// One entry per block in [`BMFont::unknown_blocks`].
+    pub unknown: Vec<usize>,
+}
+
+impl BlockSizes {
+    /// The whole binary file: magic, version, then each block with its header.
+    pub fn total(&self) -> usize {
+        let known = [
+            self.info,
+            self.common,
+            Some(self.pages),
+            Some(self.chars),
+            self.kerning,
+        ];
+        BMF_MAGIC.len()
+            + 1
+            + known
+                .iter()
+                .flatten()
+                .chain(&self.unknown)
+                .map(|size| BLOCK_HEADER_SIZE + size)
+                .sum::<usize>()
+    }
+}
+
+impl BMFont {
+    /// The size of each block [`BMFont::write_to`] would write.
+    pub fn block_sizes(&self) -> BlockSizes {
+        BlockSizes {
+            info: self
+                .info
+                .as_ref()
+                .map(|info| INFO_FIXED_SIZE + info.font_name.len() + 1),
+            common: self.common.as_ref().map(|_| COMMON_SIZE),
+            pages: self.pages.iter().map(|page| page.len() + 1).sum(),
+            chars: self.chars.len() * crate::char_record_size(BMF_VERSION),
+            kerning: (!self.kernings.is_empty()).then(|| self.kernings.len() * KERNING_RECORD_SIZE),
+            unknown: self
+                .unknown_blocks
+                .iter()
+                .map(|(_, body)| body.len())
+                .collect(),
+        }
+    }
+
+    /// Length of [`BMFont::to_octets`], computed without serializing.
+    pub fn serialized_size(&self) -> usize {
+        self.block_sizes().total()
+    }
+
+    /// Serializes the font in the binary format straight into `w`.
+    ///
+    /// Glyphs are written in id order; the kerning block is left out when the
+    /// font has no kerning pairs. Unknown blocks follow, as they were read.
+    pub fn write_to<W: Write>(&self, w: &mut W) -> io::Result<()> {
+        w.write_all(&BMF_MAGIC)?;
+        w.write_u8(BMF_VERSION)?;
+        let sizes = self.block_sizes();
+
+        if let Some(info) = &self.info {
+            write_block_header(w, 1, INFO_FIXED_SIZE + info.font_name.len() + 1)?;
+            w.write_i16::<LittleEndian>(info.font_size)?;
+            w.write_u8(info.bit_field)?;
+            w.write_u8(info.char_set)?;
+            w.write_u16::<LittleEndian>(info.stretch_h)?;
+            w.write_u8(info.aa)?;
+            w.write_all(&info.padding)?;
+            w.write_all(&info.spacing)?;
+            w.write_u8(info.outline)?;
+            w.write_all(info.font_name.as_bytes())?;
+            w.write_u8(0)?;
+        }
+
+        if let Some(common) = &self.common {
+            write_block_header(w, 2, COMMON_SIZE)?;
+            w.write_u16::<LittleEndian>(common.line_height)?;
+            w.write_u16::<LittleEndian>(common.base)?;
+            w.write_u16::<LittleEndian>(common.scale_w)?;
+            w.write_u16::<LittleEndian>(common.scale_h)?;
+            w.write_u16::<LittleEndian>(common.pages)?;
+            w.write_u8(common.bit_field)?;
+            w.write_u8(common.alpha_chnl)?;
+            w.write_u8(common.red_chnl)?;
+            w.write_u8(common.green_chnl)?;
+            w.write_u8(common.blue_chnl)?;
+        }
+
+        write_block_header(w, 3, sizes.pages)?;
+        for page in &self.pages {
+            w.write_all(page.as_bytes())?;
+            w.write_u8(0)?;
+        }
+
+        let mut chars: Vec<&Char> = self.chars.values().collect();
+        chars.sort_by_key(|ch| ch.id);
+        write_block_header(w, 4, sizes.chars)?;
+        for ch in chars {
+            w.write_all(&ch.to_record())?;
+        }
+
+        if let Some(kerning_size) = sizes.kerning {
+            write_block_header(w, 5, kerning_size)?;
+            for kerning in &self.kernings {
+                w.write_u32::<LittleEndian>(kerning.first)?;
+                w.write_u32::<LittleEndian>(kerning.second)?;
+                w.write_i16::<LittleEndian>(kerning.amount)?;
+            }
+        }
+
+        for (block_type, body) in &self.unknown_blocks {
+            write_block_header(w, *block_type, body.len())?;
+            w.write_all(body)?;
+        }
+
+        Ok(())
+    }
+
+    /// The font in the binary format.
+    pub fn to_octets(&self) -> Vec<u8> {
+        let mut octets = Vec::with_capacity(self.serialized_size());
+        self.write_to(&mut octets)
+            .expect("writing to a Vec cannot fail");
+        octets
+    }
+
+    /// Rust source declaring `const {const_name}: &[u8]` holding the font in
+    /// the binary format, for build scripts that embed a font without
+    /// `include_bytes!`. [`BMFont::from_octets`] reads the bytes back.
+    pub fn to_rust_source(&self, const_name: &str) -> String {
+        let octets = self.to_octets();
+        let mut source = format!("pub const {const_name}: &[u8] = &[\n");
+        for line in octets.chunks(16) {
+            source.push_str("   ");
+            for octet in line {
+                source.push_str(&format!(" 0x{octet:02x},"));
+            }
+            source.push('\n');
+        }
+        source.push_str("];\n");
+        source
+    }
+}
+
+fn write_block_header<W: Write>(w: &mut W, block_type: u8, size: usize) -> io::Result<()> {
+    let size = u32::try_from(size)
+        .map_err(|_| io::Error::new(io::ErrorKind::InvalidInput, "block too large"))?;
+    w.write_u8(block_type)?;
+    w.write_u32::<LittleEndian>(size)
+}
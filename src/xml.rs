@@ -0,0 +1,254 @@
+use crate::text::{Attribute, FontBuilder};
+use crate::{BMFont, BmfError, Char};
+use std::borrow::Cow;
+use std::fmt::Write;
+
+impl BMFont {
+    /// Parses a font in the BMFont XML format.
+    ///
+    /// Only the subset of XML BMFont files use is understood: elements and
+    /// their attributes, with the declaration, comments and closing tags
+    /// skipped.
+    pub fn from_xml(xml: &str) -> Result<Self, BmfError> {
+        let xml = xml.strip_prefix('\u{FEFF}').unwrap_or(xml);
+        let mut builder = FontBuilder::default();
+        let mut pos = 0;
+        // Line of the markup at `counted`, kept running so each newline is
+        // counted once.
+        let mut line = 1;
+        let mut counted = 0;
+        while let Some(offset) = xml[pos..].find('<') {
+            let start = pos + offset;
+            line += xml[counted..start].matches('\n').count();
+            counted = start;
+            let markup = &xml[start..];
+            let skip_to = |end: &str| {
+                markup
+                    .find(end)
+                    .map(|i| start + i + end.len())
+                    .ok_or_else(|| syntax(line, format!("unterminated markup, expected {end:?}")))
+            };
+            if markup.starts_with("<!--") {
+                pos = skip_to("-->")?;
+                continue;
+            }
+            if markup.starts_with("<?") || markup.starts_with("<!") || markup.starts_with("</") {
+                pos = skip_to(">")?;
+                continue;
+            }
+
+            let (tag, attributes, len) = parse_element(markup, line)?;
+            pos = start + len;
+            match tag {
+                "font" | "pages" => (),
+                _ => {
+                    if !builder.apply(tag, &attributes, line)? {
+                        return Err(syntax(line, format!("unknown element <{tag}>")));
+                    }
+                }
+            }
+        }
+        Ok(builder.finish())
+    }
+
+    /// The font in the BMFont XML format, as [`BMFont::from_xml`] reads it.
+    /// Glyphs are written in id order.
+    pub fn to_xml(&self) -> String {
+        let mut xml = String::from("<?xml version=\"1.0\"?>\n<font>\n");
+        if let Some(info) = &self.info {
+            let list = |values: &[u8]| {
+                values
+                    .iter()
+                    .map(u8::to_string)
+                    .collect::<Vec<_>>()
+                    .join(",")
+            };
+            #[allow(unused_mut)]
+            let mut attributes = vec![
+                ("face", info.font_name.clone()),
+                ("size", info.font_size.to_string()),
+                ("bold", (info.bold() as u8).to_string()),
+                ("italic", (info.italic() as u8).to_string()),
+                ("charset", info.charset_name()),
+                ("unicode", (info.unicode() as u8).to_string()),
+                ("stretchH", info.stretch_h.to_string()),
+                ("smooth", (info.smooth() as u8).to_string()),
+                ("aa", info.aa.to_string()),
+                ("padding", list(&info.padding)),
+                ("spacing", list(&info.spacing)),
+                ("outline", info.outline.to_string()),
+            ];
+            #[cfg(feature = "extra-attributes")]
+            attributes.extend(info.extra.iter().map(|(k, v)| (k.as_str(), v.clone())));
+            push_element(&mut xml, 1, "info", &attributes);
+        }
+        if let Some(common) = &self.common {
+            let attributes = [
+                ("lineHeight", common.line_height.to_string()),
+                ("base", common.base.to_string()),
+                ("scaleW", common.scale_w.to_string()),
+                ("scaleH", common.scale_h.to_string()),
+                ("pages", common.pages.to_string()),
+                ("packed", (common.packed() as u8).to_string()),
+                ("alphaChnl", common.alpha_chnl.to_string()),
+                ("redChnl", common.red_chnl.to_string()),
+                ("greenChnl", common.green_chnl.to_string()),
+                ("blueChnl", common.blue_chnl.to_string()),
+            ];
+            push_element(&mut xml, 1, "common", &attributes);
+        }
+
+        xml.push_str("  <pages>\n");
+        for (id, page) in self.pages.iter().enumerate() {
+            push_element(
+                &mut xml,
+                2,
+                "page",
+                &[("id", id.to_string()), ("file", page.clone())],
+            );
+        }
+        xml.push_str("  </pages>\n");
+
+        let mut chars: Vec<&Char> = self.chars.values().collect();
+        chars.sort_by_key(|ch| ch.id);
+        writeln!(xml, "  <chars count=\"{}\">", chars.len()).unwrap();
+        for ch in chars {
+            #[allow(unused_mut)]
+            let mut attributes = vec![
+                ("id", ch.id.to_string()),
+                ("x", ch.x.to_string()),
+                ("y", ch.y.to_string()),
+                ("width", ch.width.to_string()),
+                ("height", ch.height.to_string()),
+                ("xoffset", ch.x_offset.to_string()),
+                ("yoffset", ch.y_offset.to_string()),
+                ("xadvance", ch.x_advance.to_string()),
+                ("page", ch.page.to_string()),
+                ("chnl", ch.chnl.to_string()),
+            ];
+            #[cfg(feature = "extra-attributes")]
+            attributes.extend(ch.extra.iter().map(|(k, v)| (k.as_str(), v.clone())));
+            push_element(&mut xml, 2, "char", &attributes);
+        }
+        xml.push_str("  </chars>\n");
+
+        if !self.kernings.is_empty() {
+            writeln!(xml, "  <kernings count=\"{}\">", self.kernings.len()).unwrap();
+            for kerning in &self.kernings {
+                let attributes = [
+                    ("first", kerning.first.to_string()),
+                    ("second", kerning.second.to_string()),
+                    ("amount", kerning.amount.to_string()),
+                ];
+                push_element(&mut xml, 2, "kerning", &attributes);
+            }
+            xml.push_str("  </kernings>\n");
+        }
+        xml.push_str("</font>\n");
+        xml
+    }
+}
+
+/// Appends `<tag key="value" .../>` on its own line, `depth` levels deep.
+fn push_element(xml: &mut String, depth: usize, tag: &str, attributes: &[(&str, String)]) {
+    write!(xml, "{}<{tag}", "  ".repeat(depth)).unwrap();
+    for (key, value) in attributes {
+        write!(xml, " {key}=\"{}\"", encode_entities(value)).unwrap();
+    }
+    xml.push_str("/>\n");
+}
+
+fn encode_entities(value: &str) -> Cow<'_, str> {
+    if !value.contains(['&', '<', '>', '"']) {
+        return Cow::Borrowed(value);
+    }
+    let mut encoded = String::with_capacity(value.len());
+    for c in value.chars() {
+        match c {
+            '&' => encoded.push_str("&amp;"),
+            '<' => encoded.push_str("&lt;"),
+            '>' => encoded.push_str("&gt;"),
+            '"' => encoded.push_str("&quot;"),
+            _ => encoded.push(c),
+        }
+    }
+    Cow::Owned(encoded)
+}
+
+/// Parses `<tag key="value" ...>` or `<tag ... />` at the start of `markup`,
+/// returning the tag, its attributes and the length of the element.
+fn parse_element(markup: &str, line: usize) -> Result<(&str, Vec<Attribute<'_>>, usize), BmfError> {
+    let body = &markup[1..];
+    let tag_end = body
+        .find(|c: char| c.is_whitespace() || c == '/' || c == '>')
+        .unwrap_or(body.len());
+    let tag = &body[..tag_end];
+    let mut rest = &body[tag_end..];
+
+    let mut attributes = Vec::new();
+    loop {
+        rest = rest.trim_start();
+        if let Some(after) = rest.strip_prefix("/>").or_else(|| rest.strip_prefix('>')) {
+            return Ok((tag, attributes, markup.len() - after.len()));
+        }
+        let eq = rest
+            .find('=')
+            .ok_or_else(|| syntax(line, format!("malformed attributes in <{tag}>")))?;
+        let key = rest[..eq].trim();
+        rest = rest[eq + 1..].trim_start();
+        let quote = rest
+            .chars()
+            .next()
+            .filter(|c| *c == '"' || *c == '\'')
+            .ok_or_else(|| syntax(line, format!("unquoted value for {key} in <{tag}>")))?;
+        rest = &rest[1..];
+        let end = rest
+            .find(quote)
+            .ok_or_else(|| syntax(line, format!("unterminated value for {key} in <{tag}>")))?;
+        attributes.push((key, decode_entities(&rest[..end])));
+        rest = &rest[end + 1..];
+    }
+}
+
+fn decode_entities(value: &str) -> Cow<'_, str> {
+    if !value.contains('&') {
+        return Cow::Borrowed(value);
+    }
+    let mut decoded = String::with_capacity(value.len());
+    let mut rest = value;
+    while let Some(amp) = rest.find('&') {
+        decoded.push_str(&rest[..amp]);
+        rest = &rest[amp..];
+        let entity = rest.find(';').map(|end| (&rest[1..end], end + 1));
+        let replacement = entity.and_then(|(name, _)| match name {
+            "quot" => Some('"'),
+            "apos" => Some('\''),
+            "lt" => Some('<'),
+            "gt" => Some('>'),
+            "amp" => Some('&'),
+            _ => {
+                let code = match name.strip_prefix("#x") {
+                    Some(hex) => u32::from_str_radix(hex, 16).ok(),
+                    None => name.strip_prefix('#').and_then(|dec| dec.parse().ok()),
+                };
+                code.and_then(char::from_u32)
+            }
+        });
+        match (replacement, entity) {
+            (Some(c), Some((_, len))) => {
+                decoded.push(c);
+                rest = &rest[len..];
+            }
+            _ => {
+                decoded.push('&');
+                rest = &rest[1..];
+            }
+        }
+    }
+    decoded.push_str(rest);
+    Cow::Owned(decoded)
+}
+
+fn syntax(line: usize, message: String) -> BmfError {
+    BmfError::Syntax { line, message }
+}
@@ -0,0 +1,177 @@
+mod common;
+use common::menu_font;
+
+#[test]
+fn uv_rect_and_layer() {
+    let mut bmf = menu_font();
+    let a = bmf.chars[&('A' as u32)].clone();
+
+    let uv = [458.0 / 512.0, 126.0 / 512.0, 511.0 / 512.0, 183.0 / 512.0];
+    assert_eq!(bmf.uv_rect(&a), Some(uv));
+    assert_eq!(bmf.layered_uv(&a), Some((0, uv)));
+
+    let mut on_second_page = a.clone();
+    on_second_page.page = 1;
+    assert_eq!(bmf.layered_uv(&on_second_page), Some((1, uv)));
+
+    bmf.common.as_mut().unwrap().scale_w = 0;
+    assert_eq!(bmf.uv_rect(&a), None);
+    assert_eq!(bmf.layered_uv(&a), None);
+}
+
+#[test]
+fn uv_rect_without_common_block() {
+    let mut bmf = menu_font();
+    bmf.common = None;
+    let a = &bmf.chars[&('A' as u32)];
+
+    assert_eq!(bmf.uv_rect(a), None);
+    assert_eq!(
+        bmf.uv_rect_with_size(a, 1024, 256),
+        Some([458.0 / 1024.0, 126.0 / 256.0, 511.0 / 1024.0, 183.0 / 256.0])
+    );
+    assert_eq!(bmf.uv_rect_with_size(a, 1024, 0), None);
+}
+
+#[test]
+fn uv_rect_for_padded_texture() {
+    let bmf = menu_font();
+    let a = &bmf.chars[&('A' as u32)];
+
+    assert_eq!(
+        bmf.uv_rect_for_texture(a, 1024, 1024),
+        [
+            458.0 / 1024.0,
+            126.0 / 1024.0,
+            511.0 / 1024.0,
+            183.0 / 1024.0
+        ]
+    );
+    assert_eq!(bmf.uv_rect_for_texture(a, 0, 1024), [0.0; 4]);
+}
+
+#[test]
+fn used_rects_largest_first() {
+    use bmf_parser::Rect;
+    use std::collections::HashSet;
+
+    let bmf = menu_font();
+    let used = HashSet::from([' ' as u32, 'A' as u32, 0x2603]);
+
+    assert_eq!(
+        bmf.used_rects(&used),
+        [
+            (
+                'A' as u32,
+                Rect {
+                    x: 458,
+                    y: 126,
+                    width: 53,
+                    height: 57
+                }
+            ),
+            (
+                ' ' as u32,
+                Rect {
+                    x: 498,
+                    y: 184,
+                    width: 9,
+                    height: 7
+                }
+            ),
+        ]
+    );
+}
+
+#[test]
+fn duplicate_rects_grouped_per_page() {
+    let mut bmf = menu_font();
+    assert!(bmf.find_duplicate_rects().is_empty());
+
+    let a = bmf.chars[&('A' as u32)].clone();
+    for id in ['B' as u32, 'C' as u32, 'D' as u32] {
+        let ch = bmf.chars.get_mut(&id).unwrap();
+        (ch.x, ch.y, ch.width, ch.height) = (a.x, a.y, a.width, a.height);
+    }
+    bmf.chars.get_mut(&('D' as u32)).unwrap().page = 1;
+    for id in ['E' as u32, 'F' as u32] {
+        let ch = bmf.chars.get_mut(&id).unwrap();
+        (ch.x, ch.y, ch.width, ch.height) = (0, 0, 0, 0);
+    }
+
+    assert_eq!(bmf.find_duplicate_rects(), [vec![65, 66, 67]]);
+}
+
+#[test]
+fn glyph_count_per_page_covers_stray_pages() {
+    let mut bmf = menu_font();
+    let total = bmf.chars.len();
+    assert_eq!(bmf.glyph_count_per_page(), [total]);
+
+    bmf.pages.push("second.png".to_string());
+    bmf.chars.get_mut(&('A' as u32)).unwrap().page = 3;
+
+    assert_eq!(bmf.glyph_count_per_page(), [total - 1, 0, 0, 1]);
+}
+
+#[test]
+fn glyph_area_and_aspect_ratio() {
+    let bmf = menu_font();
+    let a = bmf['A'].clone();
+
+    assert_eq!((a.width, a.height), (53, 57));
+    assert_eq!(a.area(), 53 * 57);
+    assert_eq!(a.aspect_ratio(), 53.0 / 57.0);
+
+    let mut flat = a;
+    flat.height = 0;
+    assert_eq!(flat.area(), 0);
+    assert_eq!(flat.aspect_ratio(), 0.0);
+}
+
+#[test]
+fn glyphs_on_page_and_page_file() {
+    let mut bmf = menu_font();
+    let total = bmf.chars.len();
+    assert_eq!(bmf.glyphs_on_page(0).count(), total);
+    assert_eq!(bmf.page_file(0), Some("test_0.png"));
+
+    assert_eq!(bmf.glyphs_on_page(1).count(), 0);
+    assert_eq!(bmf.page_file(1), None);
+
+    // A glyph on a page the pages block does not list.
+    bmf.chars.get_mut(&('A' as u32)).unwrap().page = 2;
+
+    let stray: Vec<u32> = bmf.glyphs_on_page(2).map(|ch| ch.id).collect();
+    assert_eq!(stray, ['A' as u32]);
+    assert_eq!(bmf.page_file(2), None);
+    assert_eq!(bmf.glyphs_on_page(0).count(), total - 1);
+}
+
+#[test]
+fn atlas_bytes_per_page() {
+    let mut bmf = menu_font();
+    assert_eq!(bmf.atlas_bytes(4), 512 * 512 * 4);
+
+    bmf.pages.push("second.png".to_string());
+    assert_eq!(bmf.atlas_bytes(1), 2 * 512 * 512);
+
+    bmf.common = None;
+    assert_eq!(bmf.atlas_bytes(4), 0);
+}
+
+#[test]
+fn glyph_at_atlas_pixel() {
+    let mut bmf = menu_font();
+    let id_at =
+        |bmf: &bmf_parser::BMFont, page, x, y| bmf.glyph_at_atlas(page, x, y).map(|ch| ch.id);
+
+    assert_eq!(id_at(&bmf, 0, 458, 126), Some(65));
+    assert_eq!(id_at(&bmf, 0, 510, 182), Some(65));
+    assert_ne!(id_at(&bmf, 0, 511, 182), Some(65));
+    assert_eq!(id_at(&bmf, 1, 458, 126), None);
+
+    let b = bmf.chars.get_mut(&('B' as u32)).unwrap();
+    (b.x, b.y) = (458, 126);
+    assert_eq!(id_at(&bmf, 0, 460, 130), Some(65));
+}
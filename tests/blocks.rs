@@ -0,0 +1,305 @@
+use bmf_parser::{BMFont, BlockKind, BmfError, InfoBlock, ParseWarning};
+
+fn block(block_type: u8, body: &[u8]) -> Vec<u8> {
+    let mut octets = vec![block_type];
+    octets.extend_from_slice(&(body.len() as u32).to_le_bytes());
+    octets.extend_from_slice(body);
+    octets
+}
+
+fn font(blocks: &[Vec<u8>]) -> Vec<u8> {
+    let mut octets = vec![66, 77, 70, 3];
+    for b in blocks {
+        octets.extend_from_slice(b);
+    }
+    octets
+}
+
+fn info_body(name: &[u8]) -> Vec<u8> {
+    let mut body = vec![32, 0, 0b1100_0000, 0, 100, 0, 1, 0, 0, 0, 0, 1, 1, 0];
+    body.extend_from_slice(name);
+    body
+}
+
+#[test]
+fn font_name_stops_at_first_null() {
+    let octets = font(&[block(1, &info_body(b"Arial\0\xffgarbage\0"))]);
+
+    let bmf = BMFont::from_octets(&octets).unwrap();
+
+    assert_eq!(bmf.info.unwrap().font_name, "Arial");
+}
+
+#[test]
+fn lenient_collects_warnings() {
+    let octets = font(&[
+        block(1, &info_body(b"Ari\xffl\0")),
+        block(9, &[1, 2, 3]),
+        block(3, b"page.png\0"),
+    ]);
+
+    let (bmf, warnings) = BMFont::from_octets_lenient(&octets);
+
+    assert_eq!(bmf.unwrap().info.unwrap().font_name, "Ari\u{fffd}l");
+    assert!(matches!(
+        warnings[..],
+        [
+            ParseWarning::InvalidUtf8 { block: 1 },
+            ParseWarning::UnknownBlock { block: 9 }
+        ]
+    ));
+    assert!(BMFont::from_octets(&octets).is_err());
+}
+
+#[test]
+fn strict_requires_info_and_common() {
+    let no_common = font(&[block(1, &info_body(b"Arial\0"))]);
+    let no_info = font(&[block(2, &[0; 15])]);
+
+    assert!(matches!(
+        BMFont::from_octets_strict(&no_common),
+        Err(BmfError::MissingBlock(BlockKind::Common))
+    ));
+    assert!(matches!(
+        BMFont::from_octets_strict(&no_info),
+        Err(BmfError::MissingBlock(BlockKind::Info))
+    ));
+    let menu = std::fs::read("assets/menu.fnt").unwrap();
+    assert!(BMFont::from_octets_strict(&menu).is_ok());
+}
+
+#[test]
+fn negative_font_size_matches_char_height() {
+    let mut body = info_body(b"Arial\0");
+    body[..2].copy_from_slice(&(-32i16).to_le_bytes());
+    let octets = font(&[block(1, &body)]);
+
+    let info = BMFont::from_octets(&octets).unwrap().info.unwrap();
+
+    assert_eq!(info.font_size, -32);
+    assert_eq!(info.requested_size(), 32);
+    assert!(info.matches_char_height());
+}
+
+#[test]
+fn stretch_factor() {
+    let octets = font(&[block(1, &info_body(b"Arial\0"))]);
+    let mut info = BMFont::from_octets(&octets).unwrap().info.unwrap();
+    assert_eq!(info.stretch_h, 100);
+    assert_eq!(info.stretch_factor(), 1.0);
+
+    info.stretch_h = 150;
+    assert_eq!(info.stretch_factor(), 1.5);
+
+    info.stretch_h = 0;
+    assert_eq!(info.stretch_factor(), 1.0);
+}
+
+#[test]
+fn antialiasing_level() {
+    let menu = BMFont::from_octets(&std::fs::read("assets/menu.fnt").unwrap()).unwrap();
+    let menu_info = menu.info.unwrap();
+    assert_eq!(menu_info.antialiasing_level(), 1);
+    assert!(!menu_info.is_antialiased());
+
+    let info = |aa| InfoBlock {
+        font_size: 32,
+        bit_field: 0,
+        char_set: 0,
+        stretch_h: 100,
+        aa,
+        padding: [0; 4],
+        spacing: [1, 1],
+        outline: 0,
+        font_name: "Arial".to_string(),
+        #[cfg(feature = "extra-attributes")]
+        extra: Default::default(),
+    };
+    assert_eq!(info(1).antialiasing_level(), 1);
+    assert!(!info(1).is_antialiased());
+    assert_eq!(info(4).antialiasing_level(), 4);
+    assert!(info(4).is_antialiased());
+}
+
+#[test]
+fn reserved_info_bits_are_the_low_bits() {
+    let plain = BMFont::from_octets(&font(&[block(1, &info_body(b"Arial\0"))])).unwrap();
+    assert_eq!(plain.info.unwrap().reserved_info_bits(), 0);
+
+    let mut body = info_body(b"Arial\0");
+    // Smooth (bit 0) plus reserved bit 5, counting from the most significant bit.
+    body[2] = 0b1000_0100;
+    let octets = font(&[block(1, &body)]);
+
+    let info = BMFont::from_octets(&octets).unwrap().info.unwrap();
+
+    assert!(info.smooth() && !info.unicode());
+    assert_eq!(info.reserved_info_bits(), 0b0000_0100);
+}
+
+#[test]
+fn empty_font_name_round_trips() {
+    let octets = font(&[block(1, &info_body(b"\0\0\0\0"))]);
+
+    let bmf = BMFont::from_octets(&octets).unwrap();
+    let written = BMFont::from_octets(&bmf.to_octets()).unwrap();
+    let text = BMFont::from_text(&bmf.to_text()).unwrap();
+
+    assert_eq!(bmf.info.as_ref().unwrap().font_name, "");
+    assert_eq!(written.info, bmf.info);
+    assert_eq!(text.info, bmf.info);
+    assert!(bmf.to_text().starts_with("info face=\"\" "));
+}
+
+#[test]
+fn zeroed_chars_block_warns() {
+    let octets = font(&[block(4, &[0; 3 * 20])]);
+
+    let (bmf, warnings) = BMFont::from_octets_lenient(&octets);
+
+    assert_eq!(bmf.unwrap().chars.len(), 1);
+    assert!(matches!(
+        warnings[..],
+        [
+            ParseWarning::ZeroedCharsBlock { records: 3 },
+            ParseWarning::DuplicateGlyphIds {
+                records: 3,
+                distinct: 1
+            },
+            ..
+        ]
+    ));
+}
+
+#[test]
+fn common_block_without_channels() {
+    let body = [33, 0, 26, 0, 0, 1, 0, 1, 1, 0, 0];
+    let octets = font(&[block(2, &body)]);
+
+    let common = BMFont::from_octets(&octets).unwrap().common.unwrap();
+
+    assert_eq!(
+        (common.line_height, common.base, common.scale_w),
+        (33, 26, 256)
+    );
+    assert_eq!(common.pages, 1);
+    assert_eq!(
+        [
+            common.alpha_chnl,
+            common.red_chnl,
+            common.green_chnl,
+            common.blue_chnl
+        ],
+        [0; 4]
+    );
+    assert!(BMFont::from_octets(&font(&[block(2, &body[..10])])).is_err());
+}
+
+#[test]
+fn chars_list_keeps_duplicates() {
+    let mut record = [0u8; 20];
+    record[..4].copy_from_slice(&65u32.to_le_bytes());
+    let mut other = record;
+    other[4] = 7;
+    let chars = [record, other, record, [0; 20]].concat();
+    let octets = font(&[block(4, &chars)]);
+
+    let list = bmf_parser::parse_chars_list(&octets).unwrap();
+
+    assert_eq!(list.len(), 4);
+    assert_eq!(list[1].x, 7);
+    assert_eq!(bmf_parser::duplicate_ids(&list), [65]);
+    assert_eq!(BMFont::from_octets(&octets).unwrap().chars.len(), 2);
+}
+
+#[test]
+fn chars_raw_takes_the_block_the_parser_keeps() {
+    let mut first = [0u8; 20];
+    first[..4].copy_from_slice(&65u32.to_le_bytes());
+    let mut second = [0u8; 20];
+    second[..4].copy_from_slice(&66u32.to_le_bytes());
+    let octets = font(&[block(4, &first), block(4, &second)]);
+
+    let bmf = BMFont::from_octets(&octets).unwrap();
+
+    assert_eq!(bmf_parser::chars_raw(&octets), Some(&second[..]));
+    assert_eq!(bmf.chars.keys().collect::<Vec<_>>(), [&66]);
+    assert_eq!(bmf_parser::parse_chars_list(&octets).unwrap().len(), 1);
+}
+
+#[test]
+fn small_font_matches_bmfont_on_duplicates_and_unknown_blocks() {
+    let mut record = [0u8; 20];
+    record[..4].copy_from_slice(&65u32.to_le_bytes());
+    let mut other = record;
+    other[4] = 7;
+    let chars = [record, other, [0; 20]].concat();
+    let octets = font(&[block(4, &chars), block(100, b"tag")]);
+
+    let bmf = BMFont::from_octets(&octets).unwrap();
+    let bmf_parser::ParsedFont::Small(small) =
+        bmf_parser::ParsedFont::from_octets(&octets).unwrap()
+    else {
+        panic!("expected a small font");
+    };
+
+    assert_eq!(small.chars.len(), 2);
+    assert_eq!(small.glyph(65u32).unwrap().x, 7);
+    assert_eq!(small.glyph(65u32), bmf.glyph(65u32));
+    assert_eq!(small.unknown_blocks, bmf.unknown_blocks);
+}
+
+#[test]
+fn custom_blocks_are_preserved() {
+    let tag = br#"{"source":"atlas-tool"}"#;
+    let octets = font(&[
+        block(1, &info_body(b"Arial\0")),
+        block(100, tag),
+        block(3, b"page.png\0"),
+    ]);
+
+    let bmf = BMFont::from_octets(&octets).unwrap();
+    assert_eq!(bmf.custom_block(100), Some(&tag[..]));
+    assert_eq!(bmf.custom_block(101), None);
+
+    let written = bmf.to_octets();
+    assert_eq!(written.len(), bmf.serialized_size());
+    let reread = BMFont::from_octets(&written).unwrap();
+    assert_eq!(reread.custom_block(100), Some(&tag[..]));
+}
+
+#[test]
+fn loosely_separated_page_names() {
+    let unterminated = font(&[block(3, b"a.png\0b.png")]);
+    let newlines = font(&[block(3, b"a.png\r\nb.png\n")]);
+
+    for octets in [unterminated, newlines] {
+        let bmf = BMFont::from_octets(&octets).unwrap();
+        assert_eq!(bmf.pages, ["a.png", "b.png"]);
+        let borrowed = bmf_parser::BMFontRef::from_octets(&octets).unwrap();
+        assert_eq!(borrowed.pages, ["a.png", "b.png"]);
+    }
+}
+
+#[test]
+fn blocks_in_any_order() {
+    let octets = std::fs::read("assets/menu.fnt").unwrap();
+    let mut blocks = Vec::new();
+    let mut rest = &octets[4..];
+    while !rest.is_empty() {
+        let size = u32::from_le_bytes(rest[1..5].try_into().unwrap()) as usize;
+        blocks.push(rest[..5 + size].to_vec());
+        rest = &rest[5 + size..];
+    }
+    blocks.reverse();
+
+    let expected = BMFont::from_octets(&octets).unwrap();
+    let reordered = BMFont::from_octets_strict(&font(&blocks)).unwrap();
+
+    assert_eq!(blocks[0][0], 5);
+    assert_eq!(reordered.info, expected.info);
+    assert_eq!(reordered.common, expected.common);
+    assert_eq!(reordered.pages, expected.pages);
+    assert!(reordered.diff(&expected).is_empty());
+    assert!(reordered.validate().is_empty());
+}
@@ -0,0 +1,12 @@
+use bmf_parser::BMFont;
+use std::fs;
+
+/// The bytes of the binary font in `assets/menu.fnt`.
+pub fn menu_octets() -> Vec<u8> {
+    fs::read("assets/menu.fnt").unwrap()
+}
+
+/// The binary font in `assets/menu.fnt`.
+pub fn menu_font() -> BMFont {
+    BMFont::from_octets(&menu_octets()).unwrap()
+}
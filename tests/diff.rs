@@ -0,0 +1,78 @@
+use bmf_parser::{FontChange, KerningPair};
+
+mod common;
+use common::menu_font;
+
+#[test]
+fn diff_reports_changes() {
+    let old = menu_font();
+    let mut new = old.clone();
+    assert!(old.diff(&new).is_empty());
+
+    new.chars.get_mut(&('A' as u32)).unwrap().x_advance += 1;
+    new.chars.remove(&('B' as u32));
+    new.kernings.push(KerningPair {
+        first: 0x10FFFF,
+        second: 0x10FFFF,
+        amount: -3,
+    });
+
+    let diff = old.diff(&new);
+
+    assert_eq!(diff.len(), 3);
+    assert!(
+        matches!(diff.changes[0], FontChange::GlyphChanged { ref old, .. } if old.id == 'A' as u32)
+    );
+    assert!(matches!(diff.changes[1], FontChange::GlyphRemoved(ref ch) if ch.id == 'B' as u32));
+    assert!(matches!(diff.changes[2], FontChange::KerningAdded(_)));
+    assert_eq!(diff.to_string().lines().count(), 3);
+}
+
+#[test]
+fn diff_sees_duplicate_kerning_pairs_as_kerning_does() {
+    let pair = |amount| KerningPair {
+        first: 'A' as u32,
+        second: 'V' as u32,
+        amount,
+    };
+    let mut old = menu_font();
+    old.set_kernings([]);
+    old.kernings.extend([pair(-2), pair(-5)]);
+    let mut new = old.clone();
+    new.kernings.truncate(1);
+    assert!(old.diff(&new).is_empty());
+
+    new.kernings = vec![pair(-5), pair(-2)];
+    assert_ne!(old.kerning('A', 'V'), new.kerning('A', 'V'));
+
+    let diff = old.diff(&new);
+
+    assert_eq!(diff.len(), 1);
+    assert!(matches!(
+        diff.changes[0],
+        FontChange::KerningChanged {
+            old: -2,
+            new: -5,
+            ..
+        }
+    ));
+}
+
+#[test]
+fn metric_drift() {
+    let baseline = menu_font();
+    let mut new = baseline.clone();
+    new.chars.get_mut(&('A' as u32)).unwrap().x_advance += 3;
+    new.chars.get_mut(&('B' as u32)).unwrap().y_offset -= 1;
+    new.chars.get_mut(&('C' as u32)).unwrap().x += 40;
+    new.chars.remove(&('D' as u32));
+
+    let drift = new.metric_drift(&baseline, 0);
+    assert_eq!(drift.len(), 2);
+    assert_eq!((drift[0].id, drift[0].x_advance), ('A' as u32, 3));
+    assert_eq!((drift[1].id, drift[1].y_offset), ('B' as u32, -1));
+
+    let drift = new.metric_drift(&baseline, 1);
+    assert_eq!(drift.len(), 1);
+    assert_eq!(drift[0].max_change(), 3);
+}
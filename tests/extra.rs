@@ -0,0 +1,34 @@
+#![cfg(feature = "extra-attributes")]
+
+use bmf_parser::BMFont;
+use std::fs;
+
+#[test]
+fn unknown_attributes_round_trip() {
+    let text = fs::read_to_string("assets/hiero.fnt").unwrap().replace(
+        "char id=65 ",
+        "char id=65 letter=\"A\" note=\"hand kerned\" ",
+    );
+
+    let bmf = BMFont::from_text(&text).unwrap();
+    let reread = BMFont::from_text(&bmf.to_text()).unwrap();
+
+    let extra = &bmf['A'].extra;
+    assert_eq!(extra["letter"], "A");
+    assert_eq!(extra["note"], "hand kerned");
+    assert!(bmf['V'].extra.is_empty());
+    assert!(bmf.info.as_ref().unwrap().extra.is_empty());
+    assert_eq!(reread['A'].extra, *extra);
+}
+
+#[test]
+fn unknown_attributes_survive_xml() {
+    let text = fs::read_to_string("assets/hiero.fnt")
+        .unwrap()
+        .replace("char id=65 ", "char id=65 letter=\"A&B\" ");
+
+    let bmf = BMFont::from_text(&text).unwrap();
+    let reread = BMFont::from_xml(&bmf.to_xml()).unwrap();
+
+    assert_eq!(reread['A'].extra["letter"], "A&B");
+}
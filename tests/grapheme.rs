@@ -0,0 +1,65 @@
+#![cfg(feature = "graphemes")]
+
+use bmf_parser::BMFont;
+
+mod common;
+use common::menu_font;
+
+fn accented_font() -> BMFont {
+    let mut bmf = menu_font();
+    // Give the combining acute accent a glyph with its own advance.
+    let mut accent = bmf['A'].clone();
+    accent.id = 0x0301;
+    bmf.chars.insert(accent.id, accent);
+    bmf
+}
+
+#[test]
+fn combining_marks_do_not_advance() {
+    let bmf = accented_font();
+
+    assert_eq!(bmf.measure_graphemes("A\u{301}A"), bmf.measure("AA"));
+    assert!(bmf.measure("A\u{301}A") > bmf.measure("AA"));
+    assert_eq!(bmf.measure_graphemes("Play"), bmf.measure("Play"));
+}
+
+#[test]
+fn emoji_sequences_advance_once() {
+    let mut bmf = accented_font();
+    let mut emoji = bmf['A'].clone();
+    emoji.id = 0x1F469;
+    bmf.chars.insert(emoji.id, emoji.clone());
+    let advance = emoji.x_advance as u32;
+
+    // Woman, skin tone, ZWJ, woman: one cluster.
+    assert_eq!(
+        bmf.measure_graphemes("\u{1F469}\u{1F3FD}\u{200D}\u{1F469}"),
+        advance
+    );
+    // Two French flags of two regional indicators each.
+    for id in [0x1F1EB, 0x1F1F7] {
+        emoji.id = id;
+        bmf.chars.insert(id, emoji.clone());
+    }
+    assert_eq!(
+        bmf.measure_graphemes("\u{1F1EB}\u{1F1F7}\u{1F1EB}\u{1F1F7}"),
+        2 * advance
+    );
+}
+
+#[test]
+fn clusters_follow_uax29() {
+    let mut bmf = accented_font();
+    let mut glyph = bmf['A'].clone();
+    for id in [0x995, 0x9BF, 0x1100, 0x1161, 0x1F469] {
+        glyph.id = id;
+        bmf.chars.insert(id, glyph.clone());
+    }
+    let advance = glyph.x_advance as u32;
+
+    // Bengali ka + vowel sign I (a SpacingMark), and conjoining Hangul L + V.
+    assert_eq!(bmf.measure_graphemes("\u{995}\u{9BF}"), advance);
+    assert_eq!(bmf.measure_graphemes("\u{1100}\u{1161}"), advance);
+    // A ZWJ only joins a following pictograph, not a letter.
+    assert_eq!(bmf.measure_graphemes("\u{1F469}\u{200D}A"), 2 * advance);
+}
@@ -0,0 +1,356 @@
+mod common;
+use common::menu_font;
+
+#[test]
+fn measure_block() {
+    let bmf = menu_font();
+    let line_height = bmf.common.as_ref().unwrap().line_height as u32;
+    let width = bmf.measure("Play");
+
+    assert_eq!(bmf.measure_block(""), (0, 0));
+    assert_eq!(bmf.measure_block("Play"), (width, line_height));
+    assert_eq!(bmf.measure_block("Play\n"), (width, 2 * line_height));
+    assert_eq!(bmf.measure_block("P\nPlay\nl"), (width, 3 * line_height));
+}
+
+#[test]
+fn tabular_digits() {
+    let bmf = menu_font();
+    let digit = bmf.max_digit_advance().unwrap() as u32;
+
+    assert_eq!(bmf.measure_tabular("1111"), 4 * digit);
+    assert_eq!(bmf.measure_tabular("1234"), bmf.measure_tabular("8888"));
+}
+
+#[test]
+fn layout_runs() {
+    let bmf = menu_font();
+
+    let glyphs = bmf.layout("Play\nAgain", 10.0, 20.0);
+    let runs = bmf.layout_runs("Play\nAgain");
+
+    assert_eq!(glyphs.len(), 9);
+    assert_eq!(runs.len(), 1);
+    assert_eq!(runs[0].glyphs.len(), 9);
+    let a = bmf['A'].clone();
+    assert_eq!(glyphs[4].id, 'A' as u32);
+    assert_eq!(glyphs[4].x, 10.0 + a.x_offset as f32);
+    let line_height = bmf.common.as_ref().unwrap().line_height as f32;
+    assert_eq!(glyphs[4].y, 20.0 + line_height + a.y_offset as f32);
+}
+
+#[test]
+fn effective_advance_subtracts_spacing() {
+    let mut bmf = menu_font();
+    let a = bmf['A'].clone();
+    assert_eq!(bmf.info.as_ref().unwrap().spacing, [1, 1]);
+
+    assert_eq!(bmf.effective_advance(&a), a.x_advance - 1);
+
+    bmf.info = None;
+    assert_eq!(bmf.effective_advance(&a), a.x_advance);
+}
+
+#[test]
+fn tabs_advance_to_tab_stops() {
+    let bmf = menu_font();
+    let options = bmf_parser::LayoutOptions {
+        tab_width: 4,
+        ..Default::default()
+    };
+    let stop = 4 * bmf[' '].x_advance as u32;
+    let a = bmf['A'].clone();
+
+    assert_eq!(bmf.measure("\tA"), bmf.measure("A"));
+    assert_eq!(
+        bmf.measure_with_options("\tA", &options),
+        stop + a.x_advance as u32
+    );
+    assert_eq!(bmf.measure_with_options("\t\t", &options), 2 * stop);
+    assert_eq!(bmf.measure_with_options("A\t", &options), stop);
+
+    let glyphs = bmf.layout_with_options("\t\tA\n\tA", 5.0, 0.0, &options);
+    assert_eq!(glyphs[0].x, 5.0 + 2.0 * stop as f32 + a.x_offset as f32);
+    assert_eq!(glyphs[1].x, 5.0 + stop as f32 + a.x_offset as f32);
+}
+
+#[test]
+fn line_spacing_scales_line_advance() {
+    let bmf = menu_font();
+    let options = bmf_parser::LayoutOptions {
+        line_spacing: 1.5,
+        ..Default::default()
+    };
+    let line_height = bmf.common.as_ref().unwrap().line_height as f32;
+    let a = bmf['A'].clone();
+
+    let glyphs = bmf.layout_with_options("A\nA\nA", 0.0, 0.0, &options);
+    let (_, height) = bmf.measure_block_with_options("A\nA\nA", &options);
+
+    assert_eq!(glyphs[2].y, 3.0 * line_height + a.y_offset as f32);
+    assert_eq!(height, (4.5 * line_height).round() as u32);
+    assert_eq!(
+        bmf.measure_block("A\nA"),
+        bmf.measure_block_with_options("A\nA", &Default::default())
+    );
+}
+
+#[test]
+fn layout_iter_matches_layout() {
+    let bmf = menu_font();
+    let text = "Play\nAgain?";
+
+    let mut glyphs = bmf.layout_iter(text);
+
+    assert_eq!(glyphs.next(), Some(bmf.layout(text, 0.0, 0.0)[0]));
+    assert_eq!(
+        bmf.layout_iter(text).collect::<Vec<_>>(),
+        bmf.layout(text, 0.0, 0.0)
+    );
+}
+
+#[test]
+fn advance_table() {
+    let bmf = menu_font();
+
+    let table = bmf.advance_table(0x20..=0x7E);
+
+    assert_eq!(table.len(), 0x5F);
+    assert_eq!(table['A' as usize - 0x20], bmf['A'].x_advance);
+    assert_eq!(bmf.advance_table(0xE000..=0xE001), [0, 0]);
+}
+
+#[test]
+fn debug_render_marks_glyphs_and_baseline() {
+    let bmf = menu_font();
+    let common = bmf.common.clone().unwrap();
+    let glyphs = bmf.layout("AV\nA", 0.0, 0.0);
+    let width = glyphs.iter().map(|g| g.x + g.width).fold(1.0, f32::max);
+    let cell_w = (width / 80.0).ceil().max(1.0);
+    let cell_h = cell_w * 2.0;
+
+    let preview = bmf.debug_render("AV\nA");
+
+    let rows: Vec<&str> = preview.lines().collect();
+    assert!(rows.iter().all(|row| row.chars().count() <= 80));
+    assert!(preview.chars().all(|c| matches!(c, '#' | '-' | ' ' | '\n')));
+    let baselines: Vec<usize> = (0..2)
+        .map(|line| ((line * common.line_height + common.base) as f32 / cell_h) as usize)
+        .collect();
+    // Exactly the two baseline rows are marked across the whole width, with
+    // `-` wherever no glyph edge covers them.
+    for (index, row) in rows.iter().enumerate() {
+        if baselines.contains(&index) {
+            assert!(!row.contains(' '), "row {index} is not fully marked");
+        } else {
+            assert!(!row.contains('-'), "row {index} is not a baseline");
+        }
+    }
+    assert!(rows[baselines[1]].contains('-'));
+    // Each line's first `A` stands on its baseline, so its left edge crosses it.
+    for (&row, glyph) in baselines.iter().zip([glyphs[0], glyphs[2]]) {
+        let column = (glyph.x.max(0.0) / cell_w) as usize;
+        assert_eq!(rows[row].chars().nth(column), Some('#'));
+    }
+}
+
+#[test]
+fn font_metrics_trait() {
+    use bmf_parser::FontMetrics;
+
+    let bmf = menu_font();
+    let common = bmf.common.clone().unwrap();
+    let pair = bmf.kernings[0].clone();
+    let (first, second) = (
+        char::from_u32(pair.first).unwrap(),
+        char::from_u32(pair.second).unwrap(),
+    );
+
+    assert_eq!(bmf.line_height(), common.line_height as f32);
+    assert_eq!(bmf.advance('A'), Some(bmf['A'].x_advance as f32));
+    assert_eq!(bmf.advance('\u{10FFFF}'), None);
+    assert_eq!(bmf.kern(first, second), pair.amount as f32);
+
+    let mut bare = bmf.clone();
+    bare.common = None;
+    assert_eq!(bare.line_height(), 0.0);
+}
+
+#[test]
+fn line_metrics() {
+    let mut bmf = menu_font();
+    let common = bmf.common.clone().unwrap();
+
+    let metrics = bmf.line_metrics().unwrap();
+
+    assert_eq!(metrics.ascent, common.base as f32);
+    assert_eq!(bmf.descent(), metrics.descent);
+    assert_eq!(
+        metrics.ascent - metrics.descent + metrics.line_gap,
+        metrics.line_height
+    );
+    assert_eq!(metrics.line_height, common.line_height as f32);
+
+    // Cached until invalidated, and refreshed by scaling.
+    bmf.rescale_metrics(2.0);
+    let scaled = bmf.line_metrics().unwrap();
+    assert_eq!(scaled.ascent, 2.0 * common.base as f32);
+
+    bmf.common = None;
+    assert_eq!(bmf.line_metrics(), Some(scaled));
+    bmf.invalidate_line_metrics();
+    assert!(bmf.line_metrics().is_none());
+    assert_eq!(bmf.ascent(), 0.0);
+}
+
+#[test]
+fn truncate_to_width() {
+    let bmf = menu_font();
+    let full = bmf.measure("Options");
+
+    assert_eq!(bmf.truncate_to_width("Options", full, "..."), "Options");
+
+    let cut = bmf.truncate_to_width("Options", full - 1, "...");
+    assert!(cut.ends_with("...") && "Options".starts_with(cut.trim_end_matches('.')));
+    assert!(bmf.measure(&cut) < full);
+    let longer = format!("{}...", &"Options"[..cut.len() - 3 + 1]);
+    assert!(bmf.measure(&longer) > full - 1);
+
+    assert_eq!(bmf.truncate_to_width("Options", 0, "..."), "");
+}
+
+#[test]
+fn layout_vertical_columns() {
+    let bmf = menu_font();
+    let line_height = bmf.common.as_ref().unwrap().line_height as f32;
+    let a = bmf['A'].clone();
+
+    let glyphs = bmf.layout_vertical("AA\nA", 100.0, 10.0);
+
+    assert_eq!(glyphs.len(), 3);
+    assert_eq!(glyphs[1].x, glyphs[0].x);
+    assert_eq!(glyphs[1].y - glyphs[0].y, line_height);
+    assert_eq!(glyphs[2].x, glyphs[0].x - line_height);
+    assert_eq!(glyphs[2].y, 10.0 + a.y_offset as f32);
+    assert_eq!(
+        glyphs[0].x,
+        100.0 + (line_height - a.x_advance as f32) / 2.0 + a.x_offset as f32
+    );
+}
+
+#[test]
+fn advance_to_sums_to_measure() {
+    let bmf = menu_font();
+    let ids: Vec<u32> = "AVATAR".chars().map(|c| c as u32).collect();
+
+    let total: i32 = ids
+        .iter()
+        .enumerate()
+        .map(|(i, id)| bmf.advance_to(*id, ids.get(i + 1).copied()) as i32)
+        .sum();
+
+    assert_eq!(total as u32, bmf.measure("AVATAR"));
+    assert_eq!(bmf.advance_to('A' as u32, None), bmf['A'].x_advance);
+}
+
+#[test]
+fn ascii_grid_font() {
+    let bmf = bmf_parser::BMFont::from_ascii_grid("ABCAD", 8, 12, 3);
+
+    assert_eq!(bmf.chars.len(), 4);
+    let d = bmf.glyph('D').unwrap();
+    assert_eq!((d.x, d.y, d.width, d.height), (0, 12, 8, 12));
+    let common = bmf.common.as_ref().unwrap();
+    assert_eq!((common.scale_w, common.scale_h), (24, 24));
+    assert_eq!(bmf.measure("ABCD"), 32);
+    assert!(bmf.validate().is_empty());
+}
+
+#[test]
+fn layout_carries_channels() {
+    let mut bmf = menu_font();
+    bmf.chars.get_mut(&('A' as u32)).unwrap().chnl = 4;
+    bmf.chars.get_mut(&('B' as u32)).unwrap().chnl = 0;
+
+    let channels: Vec<u8> = bmf
+        .layout("AB", 0.0, 0.0)
+        .iter()
+        .map(|g| g.channels)
+        .collect();
+    assert_eq!(channels, [4, 15]);
+}
+
+#[test]
+fn wrap_and_fit() {
+    let bmf = bmf_parser::BMFont::from_ascii_grid("abcd ", 10, 20, 8);
+
+    assert_eq!(bmf.wrap("ab cd abcd\nd", 50), ["ab cd", "abcd", "d"]);
+    assert_eq!(bmf.wrap("abcdabcd", 50), ["abcdabcd"]);
+    assert!(bmf.wrap("", 50).is_empty());
+
+    assert!(bmf.fits_in("ab cd abcd", 50, 40, true));
+    assert!(!bmf.fits_in("ab cd abcd", 50, 39, true));
+    assert!(!bmf.fits_in("ab cd abcd", 50, 40, false));
+    assert!(bmf.fits_in("ab cd abcd", 100, 20, false));
+    assert!(!bmf.fits_in("abcdabcd", 50, 100, true));
+}
+
+#[test]
+fn default_kerning_tracks() {
+    let mut bmf = bmf_parser::BMFont::from_ascii_grid("AB", 10, 10, 2);
+    bmf.kernings.push(bmf_parser::KerningPair {
+        first: 'A' as u32,
+        second: 'B' as u32,
+        amount: -3,
+    });
+    bmf.default_kerning = 2;
+
+    assert_eq!(bmf.kerning('A', 'B'), -3);
+    assert_eq!(bmf.kerning('B', 'A'), 2);
+    assert_eq!(bmf.measure("ABA"), 30 - 3 + 2);
+    assert_eq!(bmf.measure_block("A\nA"), (10, 20));
+}
+
+#[test]
+fn measure_matches_layout_across_missing_glyphs() {
+    let mut bmf = menu_font();
+    bmf.default_kerning = -1;
+    let text = "A\u{E000}V \u{E000}\u{E000}A";
+
+    let last = *bmf.layout(text, 0.0, 0.0).last().unwrap();
+    let end = last.x - bmf['A'].x_offset as f32 + bmf['A'].x_advance as f32;
+
+    assert_eq!(bmf.measure(text) as f32, end);
+    assert_eq!(bmf.measure("A\u{E000}V"), bmf.measure("AV"));
+    assert_eq!(bmf.truncate_to_width(text, 1000, "..."), text);
+    assert_eq!(bmf.advance_to(0xE000, Some('A' as u32)), 0);
+    assert_eq!(bmf.advance_to('A' as u32, Some(0xE000)), bmf['A'].x_advance);
+}
+
+#[test]
+fn glyph_vertical_extent_around_baseline() {
+    let mut bmf = menu_font();
+    let a = bmf.chars[&('A' as u32)].clone();
+    assert_eq!(bmf.common.as_ref().unwrap().base, 72);
+
+    // Cap height above the baseline, a few pixels of antialiasing below it.
+    assert_eq!(bmf.glyph_vertical_extent(&a), (54, 3));
+
+    let mut apostrophe = a.clone();
+    apostrophe.y_offset = 20;
+    apostrophe.height = 10;
+    assert_eq!(bmf.glyph_vertical_extent(&apostrophe), (52, -42));
+
+    bmf.common = None;
+    assert_eq!(bmf.glyph_vertical_extent(&a), (-18, 75));
+}
+
+#[test]
+fn justify_spacing_fills_target() {
+    let bmf = menu_font();
+    let natural = bmf.measure("A A A");
+
+    assert_eq!(bmf.justify_spacing("A A A", natural + 10), 5.0);
+    assert_eq!(bmf.justify_spacing("A A A", natural), 0.0);
+    assert_eq!(bmf.justify_spacing("AAA", natural + 10), 0.0);
+}
@@ -0,0 +1,48 @@
+use bmf_parser::BMFont;
+use std::fs;
+
+fn fonts() -> (BMFont, BMFont) {
+    let menu = BMFont::from_octets(&fs::read("assets/menu.fnt").unwrap()).unwrap();
+    let text = fs::read_to_string("assets/hiero.fnt").unwrap();
+    (menu, BMFont::from_text(&text).unwrap())
+}
+
+#[test]
+fn can_merge_reports_conflicts() {
+    let (menu, hiero) = fonts();
+
+    let report = menu.can_merge(&hiero);
+
+    assert!(!report.is_compatible());
+    assert!(report.colliding_ids.contains(&('A' as u32)));
+    assert!(!report.page_overflow);
+    assert_eq!(
+        report.line_height_mismatch,
+        Some((menu.common.as_ref().unwrap().line_height, 33))
+    );
+
+    let mut crowded = menu.clone();
+    crowded.pages.resize(256, String::new());
+    assert!(crowded.can_merge(&hiero).page_overflow);
+    assert!(crowded.merge(&hiero).is_err());
+}
+
+#[test]
+fn merge_shifts_pages() {
+    let (mut menu, mut hiero) = fonts();
+    hiero.chars.retain(|id, _| *id == 0x263A || *id == 86);
+    let mut smiley = hiero['V'].clone();
+    smiley.id = 0x263A;
+    hiero.chars.insert(smiley.id, smiley);
+    let menu_v = menu['V'].clone();
+
+    menu.merge(&hiero).unwrap();
+
+    assert_eq!(menu.pages.last().unwrap(), "hiero.png");
+    assert_eq!(menu.chars[&0x263A].page as usize, menu.pages.len() - 1);
+    assert_eq!(menu['V'], menu_v);
+    assert_eq!(
+        menu.common.as_ref().unwrap().pages as usize,
+        menu.pages.len()
+    );
+}
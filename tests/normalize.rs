@@ -0,0 +1,43 @@
+#![cfg(feature = "normalization")]
+
+use bmf_parser::BMFont;
+use std::fs;
+
+fn font_with(ids: &[u32]) -> BMFont {
+    let text = fs::read_to_string("assets/hiero.fnt").unwrap();
+    let mut bmf = BMFont::from_text(&text).unwrap();
+    for &id in ids {
+        let mut glyph = bmf['A'].clone();
+        glyph.id = id;
+        bmf.chars.insert(id, glyph);
+    }
+    bmf
+}
+
+#[test]
+fn combining_sequence_finds_precomposed_glyph() {
+    let bmf = font_with(&[0xC4]);
+
+    assert_eq!(bmf.glyph_normalized("A\u{308}").unwrap().id, 0xC4);
+    assert_eq!(bmf.glyph_normalized("A").unwrap().id, 'A' as u32);
+    assert!(bmf.glyph_normalized("V\u{308}").is_none());
+    assert!(bmf.glyph_normalized("").is_none());
+}
+
+#[test]
+fn compositions_beyond_latin() {
+    // Cyrillic й, Vietnamese ệ and Hangul 가.
+    let bmf = font_with(&[0x439, 0x1EC7, 0xAC00]);
+
+    assert_eq!(bmf.glyph_normalized("\u{438}\u{306}").unwrap().id, 0x439);
+    assert_eq!(bmf.glyph_normalized("e\u{323}\u{302}").unwrap().id, 0x1EC7);
+    assert_eq!(bmf.glyph_normalized("\u{1100}\u{1161}").unwrap().id, 0xAC00);
+}
+
+#[test]
+fn marks_are_reordered_before_composing() {
+    let bmf = font_with(&[0x1ED]);
+
+    assert_eq!(bmf.glyph_normalized("o\u{328}\u{304}").unwrap().id, 0x1ED);
+    assert_eq!(bmf.glyph_normalized("o\u{304}\u{328}").unwrap().id, 0x1ED);
+}
@@ -1,10 +1,620 @@
+mod common;
+use common::{menu_font, menu_octets};
 use std::fs;
 
 #[test]
 fn test() {
-    let octets = fs::read("assets/menu.fnt").unwrap();
+    let octets = menu_octets();
 
     let bmf = bmf_parser::BMFont::from_octets(&octets).expect("could not read menu.fnt");
 
     println!("{bmf:?}");
 }
+
+#[test]
+fn load_with_missing_page() {
+    let result = bmf_parser::BMFont::load_with_pages(std::path::Path::new("assets/menu.fnt"));
+
+    assert!(matches!(result, Err(bmf_parser::BmfError::MissingPage(_))));
+}
+
+#[test]
+fn coverage() {
+    let bmf = menu_font();
+
+    let coverage = bmf.coverage_bitset();
+
+    assert_eq!(coverage.len(), bmf.chars.len());
+    assert!(bmf.chars.keys().all(|id| coverage.contains(*id)));
+    assert!(!coverage.contains(0x10FFFF));
+}
+
+#[test]
+fn concatenated() {
+    let octets = menu_octets();
+    let bundle = [octets.as_slice(), octets.as_slice()].concat();
+
+    let fonts = bmf_parser::BMFont::from_octets_multi(&bundle).unwrap();
+
+    assert_eq!(fonts.len(), 2);
+    assert_eq!(fonts[0].1, 0..octets.len());
+    assert_eq!(fonts[1].1, octets.len()..bundle.len());
+    assert_eq!(fonts[1].0.chars.len(), fonts[0].0.chars.len());
+}
+
+#[test]
+fn trailing_magic_is_not_a_font_boundary() {
+    let octets = menu_octets();
+    let data = [octets.as_slice(), b"BMF\x03garbage"].concat();
+
+    assert!(bmf_parser::BMFont::from_octets(&data).is_err());
+    assert!(bmf_parser::BMFont::from_octets_multi(&data).is_err());
+    assert!(bmf_parser::BMFontRef::from_octets(&data).is_err());
+}
+
+#[test]
+fn block_too_large() {
+    let octets = menu_octets();
+    let options = bmf_parser::ParseOptions {
+        max_block_size: 16,
+        ..Default::default()
+    };
+
+    let result = bmf_parser::BMFont::from_octets_with_options(&octets, &options);
+
+    assert!(matches!(
+        result,
+        Err(bmf_parser::BmfError::BlockTooLarge { block: 1, size: 37 })
+    ));
+}
+
+#[test]
+fn lenient_truncated() {
+    let octets = menu_octets();
+    let complete = menu_font();
+    // Cut into the fourth glyph record of the chars block.
+    let chars_start = octets
+        .windows(5)
+        .position(|w| w == [4, 0x6c, 0x07, 0, 0])
+        .unwrap()
+        + 5;
+    let cut = &octets[..chars_start + 3 * 20 + 7];
+    let options = bmf_parser::ParseOptions {
+        lenient: true,
+        ..Default::default()
+    };
+
+    assert!(bmf_parser::BMFont::from_octets(cut).is_err());
+    let bmf = bmf_parser::BMFont::from_octets_with_options(cut, &options).unwrap();
+
+    assert!(bmf.truncated);
+    assert!(!complete.truncated);
+    assert_eq!(bmf.chars.len(), 3);
+    assert_eq!(bmf.pages, complete.pages);
+}
+
+#[test]
+fn borrowed() {
+    let octets = menu_octets();
+    let owned = menu_font();
+
+    let borrowed = bmf_parser::BMFontRef::from_octets(&octets).unwrap();
+
+    assert_eq!(borrowed.pages, ["test_0.png"]);
+    assert_eq!(
+        borrowed.info.as_ref().unwrap().font_name,
+        "Franklin Gothic Medium"
+    );
+    assert_eq!(borrowed.to_owned().content_hash(), owned.content_hash());
+
+    let mut octets = octets;
+    octets.extend_from_slice(&[100, 3, 0, 0, 0, b't', b'a', b'g']);
+    let owned = bmf_parser::BMFont::from_octets(&octets).unwrap();
+
+    let borrowed = bmf_parser::BMFontRef::from_octets(&octets).unwrap();
+
+    assert_eq!(borrowed.unknown_blocks, [(100, &b"tag"[..])]);
+    let converted = borrowed.to_owned();
+    assert_eq!(converted.unknown_blocks, owned.unknown_blocks);
+    assert_eq!(converted.content_hash(), owned.content_hash());
+    assert_eq!(converted.to_octets(), owned.to_octets());
+}
+
+#[test]
+fn text_lines() {
+    let bmf = menu_font();
+
+    let text = bmf.to_text();
+
+    assert!(text.starts_with("info face=\"Franklin Gothic Medium\" size=96 "));
+    assert!(text.contains(&bmf['A'].to_text_line()));
+    assert!(bmf['A'].to_text_line().starts_with("char id=65 x="));
+}
+
+#[test]
+fn round_trip() {
+    let octets = menu_octets();
+    let bmf = menu_font();
+
+    let written = bmf.to_octets();
+    let reread = bmf_parser::BMFont::from_octets(&written).unwrap();
+
+    assert_eq!(written, octets);
+    assert_eq!(reread.content_hash(), bmf.content_hash());
+    assert!(reread.diff(&bmf).is_empty());
+}
+
+#[test]
+fn content_hash_is_pinned() {
+    let mut bmf = menu_font();
+
+    // Changing this value invalidates every hash persisted by users.
+    assert_eq!(bmf.content_hash(), 0x79d5_0ba1_7414_6daa);
+
+    bmf.kernings.reverse();
+    assert_eq!(bmf.content_hash(), 0x79d5_0ba1_7414_6daa);
+}
+
+#[test]
+fn normalized_page_names() {
+    let mut octets = menu_octets();
+    let at = octets.windows(10).position(|w| w == b"test_0.png").unwrap();
+    octets[at + 7..at + 10].copy_from_slice(b"PNG");
+    let options = bmf_parser::ParseOptions {
+        normalize_page_names: true,
+        strip_page_prefix: Some("test_".to_string()),
+        ..Default::default()
+    };
+
+    let bmf = bmf_parser::BMFont::from_octets_with_options(&octets, &options).unwrap();
+
+    assert_eq!(bmf.pages, ["0.png"]);
+    assert_eq!(bmf.raw_pages, ["test_0.PNG"]);
+}
+
+#[test]
+fn is_bmf() {
+    let octets = menu_octets();
+    let text = fs::read("assets/hiero.fnt").unwrap();
+
+    assert!(bmf_parser::is_bmf(&octets));
+    assert_eq!(octets[..3], bmf_parser::BMF_MAGIC);
+    assert!(!bmf_parser::is_bmf(b"BMF"));
+    assert!(!bmf_parser::is_bmf(b"BMF\x02"));
+    assert!(!bmf_parser::is_bmf(&[]));
+    assert!(!bmf_parser::is_bmf(&text));
+}
+
+#[test]
+fn structure() {
+    let octets = menu_octets();
+
+    assert!(bmf_parser::validate_structure(&octets).is_ok());
+    assert!(matches!(
+        bmf_parser::validate_structure(&octets[..octets.len() - 1]),
+        Err(bmf_parser::BmfError::BlockOutOfBounds { .. })
+    ));
+    assert!(matches!(
+        bmf_parser::validate_structure(&octets[1..]),
+        Err(bmf_parser::BmfError::InvalidHeader)
+    ));
+}
+
+#[test]
+fn glyphs_order_by_id() {
+    let bmf = menu_font();
+
+    let mut glyphs: Vec<bmf_parser::Char> = bmf.chars.values().cloned().collect();
+    glyphs.sort();
+    assert!(glyphs.windows(2).all(|w| w[0].id < w[1].id));
+
+    let set: std::collections::BTreeSet<bmf_parser::Char> = glyphs.into_iter().collect();
+    let key = |c: char| bmf_parser::Char {
+        id: c as u32,
+        ..bmf['A'].clone()
+    };
+    let capitals: String = set
+        .range(key('A')..=key('E'))
+        .map(|ch| char::from_u32(ch.id).unwrap())
+        .collect();
+    assert_eq!(capitals, "ABCDE");
+
+    // Only the id takes part in ordering; equality checks every field.
+    let mut moved = bmf['A'].clone();
+    moved.x += 1;
+    assert_eq!(moved.cmp(&bmf['A']), std::cmp::Ordering::Equal);
+    assert_ne!(moved, bmf['A']);
+    assert!(set.contains(&key('A')));
+}
+
+#[test]
+fn sorted_glyphs() {
+    let mut bmf = menu_font();
+
+    let glyphs = bmf.sorted_glyphs_slice();
+    assert_eq!(glyphs.len(), bmf.chars.len());
+    let index = glyphs
+        .binary_search_by_key(&('A' as u32), |ch| ch.id)
+        .unwrap();
+    assert_eq!(glyphs[index], bmf['A']);
+
+    bmf.chars.remove(&('A' as u32));
+    bmf.invalidate_sorted_glyphs();
+    assert!(bmf
+        .sorted_glyphs_slice()
+        .binary_search_by_key(&('A' as u32), |ch| ch.id)
+        .is_err());
+}
+
+#[test]
+fn rust_source() {
+    let octets = menu_octets();
+    let bmf = menu_font();
+
+    let source = bmf.to_rust_source("MENU_FONT");
+
+    assert!(source.starts_with("pub const MENU_FONT: &[u8] = &[\n"));
+    let embedded: Vec<u8> = source
+        .split(|c: char| c.is_whitespace() || c == ',')
+        .filter_map(|token| token.strip_prefix("0x"))
+        .map(|hex| u8::from_str_radix(hex, 16).unwrap())
+        .collect();
+    assert_eq!(embedded, octets);
+}
+
+#[test]
+fn channel_usage() {
+    let mut bmf = menu_font();
+    assert_eq!(bmf.channel_usage(), bmf_parser::ChannelUsage::AllChannels);
+    assert!(!bmf.is_multichannel());
+
+    bmf.chars.get_mut(&('A' as u32)).unwrap().chnl = 1;
+    bmf.chars.get_mut(&('B' as u32)).unwrap().chnl = 4;
+
+    assert_eq!(
+        bmf.channel_usage(),
+        bmf_parser::ChannelUsage::Packed { channels: 5 }
+    );
+    assert!(bmf.is_multichannel());
+}
+
+#[test]
+fn rebuild_char_index() {
+    let mut bmf = menu_font();
+    let a = bmf['A'].clone();
+    let count = bmf.chars.len();
+
+    bmf.chars.get_mut(&('A' as u32)).unwrap().id = 0xE000;
+    bmf.chars.get_mut(&('C' as u32)).unwrap().id = 'B' as u32;
+    bmf.rebuild_char_index();
+
+    assert!(bmf.glyph('A').is_none());
+    assert_eq!(bmf.chars[&0xE000].rect(), a.rect());
+    assert_eq!(bmf.chars.len(), count - 1);
+    assert_eq!(bmf['B'].id, 'B' as u32);
+    assert!(bmf.chars.iter().all(|(id, ch)| *id == ch.id));
+}
+
+#[test]
+fn glyph_id_lookups() {
+    use bmf_parser::GlyphId;
+
+    let bmf = menu_font();
+
+    assert_eq!(bmf.glyph('A'), bmf.glyph(65u32));
+    assert_eq!(bmf.glyph(GlyphId(65)), bmf.glyph('A'));
+    assert_eq!(u32::from(GlyphId::from('A')), 65);
+    let pair = bmf.kernings[0].clone();
+    assert_eq!(bmf.kerning(GlyphId(pair.first), pair.second), pair.amount);
+}
+
+#[test]
+fn index_by_char_and_id() {
+    let bmf = menu_font();
+
+    assert_eq!(bmf['A'].id, 65);
+    assert_eq!(bmf[65u32].x_advance, bmf['A'].x_advance);
+    assert!(bmf.glyph('\u{2603}').is_none());
+}
+
+#[test]
+#[should_panic(expected = "U+2603")]
+fn index_panics_on_missing_glyph() {
+    let bmf = menu_font();
+
+    let _ = &bmf['\u{2603}'];
+}
+
+#[test]
+fn clear_kerning() {
+    let mut bmf = menu_font();
+    let pair = bmf.kernings[0].clone();
+    assert_ne!(bmf.kerning(pair.first, pair.second), 0);
+
+    bmf.clear_kerning();
+
+    assert!(bmf.kernings.is_empty());
+    assert_eq!(bmf.kerning(pair.first, pair.second), 0);
+    assert_eq!(bmf.block_sizes().kerning, None);
+    let written = bmf.to_octets();
+    let reread = bmf_parser::BMFont::from_octets(&written).unwrap();
+    assert!(reread.kernings.is_empty());
+    assert!(reread.diff(&bmf).is_empty());
+    assert_eq!(reread.to_octets(), written);
+}
+
+#[test]
+fn asymmetric_kernings() {
+    let mut bmf = menu_font();
+    bmf.set_kernings([
+        (65, 86, -2),
+        (86, 65, -2),
+        (84, 111, -3),
+        (80, 65, -1),
+        (65, 80, -2),
+    ]);
+
+    assert_eq!(bmf.asymmetric_kernings(), [(65, 80), (84, 111)]);
+
+    bmf.default_kerning = -3;
+    assert_eq!(bmf.asymmetric_kernings(), [(65, 80)]);
+}
+
+#[test]
+fn max_glyphs_preview() {
+    let octets = menu_octets();
+    let complete = menu_font();
+    let options = bmf_parser::ParseOptions {
+        max_glyphs: Some(3),
+        ..Default::default()
+    };
+
+    let preview = bmf_parser::BMFont::from_octets_with_options(&octets, &options).unwrap();
+
+    assert!(preview.truncated_glyphs);
+    assert!(!complete.truncated_glyphs);
+    assert_eq!(preview.chars.len(), 3);
+    assert_eq!(preview.kernings, complete.kernings);
+}
+
+#[test]
+fn face_name() {
+    let mut bmf = menu_font();
+    assert_eq!(bmf.face_name(), Some("Franklin Gothic Medium"));
+
+    bmf.info = None;
+    assert_eq!(bmf.face_name(), None);
+    assert_eq!(bmf.face_name_or("unnamed"), "unnamed");
+}
+
+#[test]
+fn too_many_kernings() {
+    let octets = menu_octets();
+    let count = menu_font().kernings.len();
+    let options = bmf_parser::ParseOptions {
+        max_kerning_pairs: count - 1,
+        ..Default::default()
+    };
+
+    let result = bmf_parser::BMFont::from_octets_with_options(&octets, &options);
+
+    assert!(matches!(
+        result,
+        Err(bmf_parser::BmfError::TooManyKernings { count: c, max }) if c == count && max == count - 1
+    ));
+}
+
+#[test]
+fn pair_frequencies() {
+    let counts = bmf_parser::pair_frequencies("AVAV\nVA");
+
+    assert_eq!(counts[&('A', 'V')], 2);
+    assert_eq!(counts[&('V', 'A')], 2);
+    assert_eq!(counts.len(), 2);
+}
+
+#[test]
+fn chars_raw() {
+    let octets = menu_octets();
+    let bmf = menu_font();
+
+    let records = bmf_parser::chars_raw(&octets).unwrap();
+
+    assert_eq!(records.len(), 20 * bmf.chars.len());
+    let first_id = u32::from_le_bytes(records[..4].try_into().unwrap());
+    assert!(bmf.chars.contains_key(&first_id));
+    assert!(bmf_parser::chars_raw(&octets[1..]).is_none());
+}
+
+#[test]
+fn from_octets_at() {
+    let octets = menu_octets();
+    let bundle = [b"ASSETS\0\x01".as_slice(), &octets].concat();
+
+    let bmf = bmf_parser::BMFont::from_octets_at(&bundle, 8).unwrap();
+
+    assert_eq!(bmf.to_octets(), octets);
+    assert!(bmf_parser::BMFont::from_octets_at(&bundle, 0).is_err());
+    assert!(bmf_parser::BMFont::from_octets_at(&bundle, bundle.len() + 1).is_err());
+}
+
+#[test]
+fn sdf_spread() {
+    let mut bmf = menu_font();
+    assert_eq!(bmf.sdf_spread(), None);
+
+    bmf.is_sdf = true;
+    bmf.info.as_mut().unwrap().padding = [4, 6, 4, 6];
+    assert_eq!(bmf.sdf_spread(), Some(6.0));
+
+    bmf.info.as_mut().unwrap().padding = [0; 4];
+    bmf.info.as_mut().unwrap().spacing = [3, 3];
+    assert_eq!(bmf.sdf_spread(), Some(3.0));
+
+    bmf.sdf_spread_override = Some(8.5);
+    assert_eq!(bmf.sdf_spread(), Some(8.5));
+}
+
+#[test]
+fn looks_like_sdf() {
+    let mut bmf = menu_font();
+    assert!(!bmf.looks_like_sdf());
+
+    for ch in bmf.chars.values_mut() {
+        ch.height = 50;
+    }
+    let info = bmf.info.as_mut().unwrap();
+    info.padding = [5, 5, 5, 5];
+    assert!(bmf.looks_like_sdf());
+
+    // Under a tenth of the median glyph height.
+    bmf.info.as_mut().unwrap().padding = [4, 4, 4, 4];
+    assert!(!bmf.looks_like_sdf());
+
+    // Supersampled rendering is not an SDF generator's output.
+    let info = bmf.info.as_mut().unwrap();
+    info.padding = [5, 5, 5, 5];
+    info.aa = 2;
+    assert!(!bmf.looks_like_sdf());
+}
+
+#[test]
+fn kernings_involving() {
+    let mut bmf = menu_font();
+    bmf.set_kernings([(65, 86, -2), (86, 65, -2), (84, 111, -3), (65, 65, 1)]);
+
+    let involving: Vec<(u32, u32)> = bmf
+        .kernings_involving(65)
+        .map(|k| (k.first, k.second))
+        .collect();
+
+    assert_eq!(involving, [(65, 86), (86, 65), (65, 65)]);
+}
+
+#[test]
+fn small_font_representation() {
+    let octets = menu_octets();
+    let bmf = menu_font();
+
+    let parsed = bmf_parser::ParsedFont::from_octets(&octets).unwrap();
+
+    assert!(parsed.is_small());
+    assert_eq!(parsed.glyph('A'), bmf.glyph('A'));
+    assert_eq!(parsed.glyph(0x10FFFFu32), None);
+    let pair = bmf.kernings[0].clone();
+    assert_eq!(parsed.kerning(pair.first, pair.second), pair.amount);
+    let bmf_parser::ParsedFont::Small(small) = parsed else {
+        unreachable!();
+    };
+    assert!(small.into_font().diff(&bmf).is_empty());
+}
+
+#[test]
+fn small_font_keeps_font_settings() {
+    let mut bmf = menu_font();
+    bmf.default_kerning = -2;
+    bmf.raw_pages = vec!["fonts/test_0.png".to_string()];
+    bmf.unknown_blocks.push((100, b"tag".to_vec()));
+    bmf.is_sdf = true;
+
+    let small = bmf_parser::SmallBMFont::from(bmf.clone());
+
+    assert_eq!(small.kerning(0x10FFFFu32, 'A'), -2);
+    let back = small.into_font();
+    assert!(back.diff(&bmf).is_empty());
+    assert_eq!(back.default_kerning, -2);
+    assert_eq!(back.raw_pages, bmf.raw_pages);
+    assert_eq!(back.unknown_blocks, bmf.unknown_blocks);
+    assert!(back.is_sdf);
+}
+
+#[test]
+fn small_font_accepts_what_bmfont_accepts() {
+    let octets = menu_octets();
+    let bundle = [octets.as_slice(), octets.as_slice()].concat();
+
+    assert!(bmf_parser::BMFont::from_octets(&bundle).is_err());
+    assert!(bmf_parser::ParsedFont::from_octets(&bundle).is_err());
+
+    let bmf = menu_font();
+    let parsed = bmf_parser::ParsedFont::from_octets(&octets).unwrap();
+
+    assert!(parsed.is_small());
+    assert_eq!(parsed.glyph('A'), bmf.glyph('A'));
+}
+
+#[test]
+fn serialized_size() {
+    let octets = menu_octets();
+    let mut bmf = menu_font();
+
+    assert_eq!(bmf.serialized_size(), octets.len());
+    let sizes = bmf.block_sizes();
+    assert_eq!(sizes.common, Some(15));
+    assert_eq!(sizes.chars, bmf.chars.len() * 20);
+
+    bmf.kernings.clear();
+    bmf.info = None;
+    assert_eq!(bmf.block_sizes().kerning, None);
+    assert_eq!(bmf.serialized_size(), bmf.to_octets().len());
+}
+
+#[test]
+fn script_coverage() {
+    use bmf_parser::{BMFont, CoverageLevel, UnicodeScript};
+
+    let latin: String = ('A'..='Z').chain('a'..='z').collect();
+    let mut bmf = BMFont::from_ascii_grid(&latin, 8, 8, 16);
+    assert_eq!(bmf.covers_script(UnicodeScript::Latin), CoverageLevel::Full);
+    assert_eq!(bmf.covers_script(UnicodeScript::Greek), CoverageLevel::None);
+
+    bmf.chars.remove(&('q' as u32));
+    assert_eq!(
+        bmf.covers_script(UnicodeScript::Latin),
+        CoverageLevel::Partial
+    );
+}
+
+#[test]
+fn char_records() {
+    use bmf_parser::{Char, CHAR_RECORD_SIZE};
+
+    let octets = menu_octets();
+    let bmf = menu_font();
+    let records = bmf_parser::chars_raw(&octets).unwrap();
+
+    for record in records.chunks_exact(CHAR_RECORD_SIZE) {
+        let record: &[u8; CHAR_RECORD_SIZE] = record.try_into().unwrap();
+        let ch = Char::from_record(record);
+        assert_eq!(&bmf.chars[&ch.id], &ch);
+        assert_eq!(&ch.to_record(), record);
+    }
+}
+
+#[test]
+fn structure_of_arrays() {
+    let bmf = menu_font();
+
+    let soa = bmf.to_soa();
+
+    assert_eq!(soa.len(), bmf.chars.len());
+    assert_eq!(soa.x_advance.len(), soa.len());
+    assert!(soa.ids.windows(2).all(|pair| pair[0] < pair[1]));
+    let index = soa.index_of('A' as u32).unwrap();
+    assert_eq!(soa.x[index], bmf['A'].x);
+    assert_eq!(soa.x_advance[index], bmf['A'].x_advance);
+    assert_eq!(soa.index_of(0x10FFFF), None);
+}
+
+#[test]
+fn build_font_from_scratch() {
+    let mut bmf = bmf_parser::BMFont::new();
+    bmf.pages.push("atlas.png".to_string());
+    let mut glyph = bmf_parser::BMFont::from_ascii_grid("A", 8, 8, 1)['A'].clone();
+    glyph.x_advance = 9;
+    bmf.chars.insert(glyph.id, glyph);
+
+    assert_eq!(bmf.measure("AA"), 18);
+    assert_eq!(bmf.sorted_glyphs_slice().len(), 1);
+    assert!(bmf.info.is_none() && bmf.kernings.is_empty());
+}
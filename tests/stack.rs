@@ -0,0 +1,26 @@
+use bmf_parser::{BMFont, FontStack};
+use std::fs;
+
+#[test]
+fn stack_coverage() {
+    let menu = BMFont::from_octets(&fs::read("assets/menu.fnt").unwrap()).unwrap();
+    let text = fs::read_to_string("assets/hiero.fnt").unwrap();
+    let mut hiero = BMFont::from_text(&text).unwrap();
+    hiero.chars.insert(0x263A, hiero['A'].clone());
+    let stack = FontStack::new(vec![menu.clone(), hiero]);
+
+    let coverage = stack.combined_coverage();
+
+    assert!(coverage.contains('A' as u32));
+    assert!(coverage.contains(0x263A));
+    assert_eq!(stack.glyph('A').unwrap().0, 0);
+    assert_eq!(stack.glyph('\u{263A}').unwrap().0, 1);
+    assert_eq!(
+        stack.uncovered("A\u{263A}\u{2603}\nA\u{2603}"),
+        ['\u{2603}']
+    );
+    assert_eq!(
+        FontStack::new(vec![menu]).uncovered("\u{263A}"),
+        ['\u{263A}']
+    );
+}
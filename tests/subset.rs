@@ -0,0 +1,104 @@
+use std::collections::HashSet;
+
+mod common;
+use common::menu_font;
+
+#[test]
+fn subset_ranges_matches_subset() {
+    let bmf = menu_font();
+    let keep: HashSet<u32> = ('A' as u32..='Z' as u32)
+        .chain('0' as u32..='9' as u32)
+        .collect();
+
+    let by_set = bmf.subset(&keep);
+    let by_ranges = bmf.subset_ranges(&['A' as u32..='Z' as u32, '0' as u32..='9' as u32]);
+
+    assert!(!by_ranges.chars.is_empty());
+    assert!(by_set.diff(&by_ranges).is_empty());
+    assert!(by_ranges.chars.keys().all(|id| keep.contains(id)));
+    assert!(by_ranges
+        .kernings
+        .iter()
+        .all(|k| keep.contains(&k.first) && keep.contains(&k.second)));
+}
+
+#[test]
+fn subset_ranges_empty() {
+    let bmf = menu_font();
+
+    let subset = bmf.subset_ranges(&[]);
+
+    assert!(subset.chars.is_empty());
+    assert!(subset.kernings.is_empty());
+    assert_eq!(subset.pages, bmf.pages);
+}
+
+#[test]
+fn required_glyphs_feed_subset() {
+    let bmf = menu_font();
+    let strings = vec![String::from("Play"), String::from("Pause")];
+
+    let needed = bmf_parser::required_glyphs(&strings);
+    let subset = bmf.subset(&needed);
+
+    let mut ids: Vec<u32> = needed.iter().copied().collect();
+    ids.sort_unstable();
+    assert_eq!(ids, "Paelsuy".chars().map(|c| c as u32).collect::<Vec<_>>());
+    assert!(subset.chars.keys().all(|id| needed.contains(id)));
+}
+
+#[test]
+fn remove_glyphs_drops_kerning() {
+    let mut bmf = menu_font();
+    bmf.set_kernings([(65, 86, -2), (86, 65, -2), (84, 111, -3)]);
+    let count = bmf.chars.len();
+
+    let removed = bmf.remove_glyphs(&HashSet::from(['A' as u32]));
+
+    assert_eq!(removed, 2);
+    assert_eq!(bmf.chars.len(), count - 1);
+    assert!(bmf.glyph('A').is_none());
+    assert_eq!(bmf.kernings.len(), 1);
+}
+
+#[test]
+fn orphaned_pages() {
+    let mut bmf = menu_font();
+    bmf.pages.push("leftover.png".to_string());
+    assert_eq!(bmf.referenced_pages(), [0].into());
+    assert_eq!(bmf.orphaned_pages(), ["leftover.png"]);
+
+    bmf.chars.get_mut(&('A' as u32)).unwrap().page = 1;
+    assert!(bmf.orphaned_pages().is_empty());
+}
+
+#[test]
+fn split_by_page_two_pages() {
+    let mut bmf = menu_font();
+    bmf.pages.push("second.png".to_string());
+    bmf.common.as_mut().unwrap().pages = 2;
+    for id in ['y' as u32, 'o' as u32] {
+        bmf.chars.get_mut(&id).unwrap().page = 1;
+    }
+
+    let parts = bmf.split_by_page();
+
+    assert_eq!(parts.len(), 2);
+    assert_eq!(parts[0].chars.len(), bmf.chars.len() - 2);
+    let second: HashSet<u32> = parts[1].chars.keys().copied().collect();
+    assert_eq!(second, HashSet::from(['y' as u32, 'o' as u32]));
+    assert!(parts[1]
+        .kernings
+        .iter()
+        .any(|k| k.first == 'y' as u32 && k.second == 'o' as u32));
+    for (part, page) in parts.iter().zip(&bmf.pages) {
+        assert_eq!(part.pages, std::slice::from_ref(page));
+        assert_eq!(part.common.as_ref().unwrap().pages, 1);
+        assert!(part.chars.values().all(|ch| ch.page == 0));
+        assert!(part
+            .kernings
+            .iter()
+            .all(|k| part.chars.contains_key(&k.first) && part.chars.contains_key(&k.second)));
+    }
+    assert!(parts[0].kernings.len() + parts[1].kernings.len() < bmf.kernings.len());
+}
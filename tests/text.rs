@@ -0,0 +1,222 @@
+use bmf_parser::BMFont;
+use std::fs;
+
+fn check_hiero(bmf: &BMFont) {
+    let info = bmf.info.as_ref().unwrap();
+    assert_eq!(info.font_name, "Open Sans");
+    assert!(info.smooth());
+    assert_eq!(info.padding, [1, 1, 1, 1]);
+    assert_eq!(info.spacing, [0, 0]);
+    assert_eq!(info.outline, 0);
+
+    let common = bmf.common.as_ref().unwrap();
+    assert_eq!(common.line_height, 33);
+    assert_eq!(common.alpha_chnl, 0);
+
+    assert_eq!(bmf.pages, ["hiero.png"]);
+    assert_eq!(bmf.chars.len(), 4);
+    assert!(bmf.chars.values().all(|ch| ch.chnl == 0));
+    assert_eq!(bmf['A'].x_advance, 15);
+    assert_eq!(bmf['A'].x_offset, -1);
+    assert_eq!(bmf.kerning('A' as u32, 'V' as u32), -1);
+}
+
+#[test]
+fn hiero_text() {
+    let text = fs::read_to_string("assets/hiero.fnt").unwrap();
+
+    let bmf = BMFont::from_text(&text).unwrap();
+
+    check_hiero(&bmf);
+}
+
+#[test]
+fn irregular_whitespace() {
+    let text = fs::read_to_string("assets/irregular.fnt").unwrap();
+
+    let bmf = BMFont::from_text(&text).unwrap();
+
+    check_hiero(&bmf);
+    assert_eq!(bmf.info.as_ref().unwrap().font_name, "Open Sans");
+    assert_eq!(bmf.chars.len(), 4);
+    assert_eq!(bmf.kerning('V' as u32, 'A' as u32), -1);
+}
+
+#[test]
+fn tab_separated() {
+    let text = fs::read_to_string("assets/tabs.fnt").unwrap();
+    assert!(!text.contains("char id"));
+
+    let bmf = BMFont::from_text(&text).unwrap();
+
+    check_hiero(&bmf);
+    assert_eq!(bmf.kerning('A', 'V'), -1);
+}
+
+#[test]
+fn comments_and_blank_lines() {
+    let text = fs::read_to_string("assets/hiero.fnt").unwrap();
+    let mut annotated = String::from("# exported by Hiero\n\n");
+    for line in text.lines() {
+        annotated.push_str(line);
+        annotated.push_str("\n  \n// hand-edited\n\t# indented comment\n");
+    }
+
+    let bmf = BMFont::from_text(&annotated).unwrap();
+
+    check_hiero(&bmf);
+    assert_eq!(bmf.diff(&BMFont::from_text(&text).unwrap()).len(), 0);
+}
+
+#[test]
+fn bom_prefixed() {
+    let octets = fs::read("assets/bom.fnt").unwrap();
+    let text = fs::read_to_string("assets/bom.fnt").unwrap();
+    let xml = format!(
+        "\u{FEFF}{}",
+        fs::read_to_string("assets/hiero.xml").unwrap()
+    );
+
+    check_hiero(&BMFont::parse(&octets).unwrap());
+    check_hiero(&BMFont::from_text(&text).unwrap());
+    check_hiero(&BMFont::from_xml(&xml).unwrap());
+    check_hiero(&BMFont::parse(xml.as_bytes()).unwrap());
+    let binary = fs::read("assets/menu.fnt").unwrap();
+    assert!(BMFont::parse(&binary).is_ok());
+}
+
+#[test]
+fn hiero_xml() {
+    let xml = fs::read_to_string("assets/hiero.xml").unwrap();
+
+    let bmf = BMFont::from_xml(&xml).unwrap();
+
+    check_hiero(&bmf);
+}
+
+#[test]
+fn text_round_trip() {
+    let octets = fs::read("assets/menu.fnt").unwrap();
+    let bmf = BMFont::from_octets(&octets).unwrap();
+
+    let reread = BMFont::from_text(&bmf.to_text()).unwrap();
+
+    assert_eq!(reread.to_octets(), octets);
+}
+
+#[test]
+fn kerning_amount_out_of_range() {
+    let text = "kerning first=65 second=86 amount=-40000\n";
+
+    let err = BMFont::from_text(text).unwrap_err();
+
+    assert_eq!(
+        err.to_string(),
+        "line 1: value -40000 for amount is out of range"
+    );
+}
+
+#[test]
+fn csv_export() {
+    let text = fs::read_to_string("assets/hiero.fnt").unwrap();
+    let mut bmf = BMFont::from_text(&text).unwrap();
+    let mut comma = bmf['A'].clone();
+    comma.id = ',' as u32;
+    bmf.chars.insert(comma.id, comma);
+
+    let csv = bmf.to_csv();
+
+    let lines: Vec<&str> = csv.lines().collect();
+    assert_eq!(
+        lines[0],
+        "id,char,x,y,width,height,x_offset,y_offset,x_advance,page,chnl"
+    );
+    assert_eq!(lines[1], "0,U+0000,0,0,0,0,-1,0,0,0,0");
+    assert_eq!(lines[2], "32,U+0020,0,0,0,0,-1,25,6,0,0");
+    assert_eq!(lines[3], "44,\",\",2,2,17,19,-1,6,15,0,0");
+    assert_eq!(lines[4], "65,A,2,2,17,19,-1,6,15,0,0");
+    assert_eq!(lines.len(), 6);
+}
+
+#[test]
+fn empty_glyphs() {
+    let text = fs::read_to_string("assets/hiero.fnt").unwrap();
+    let bmf = BMFont::from_text(&text).unwrap();
+
+    let mut empty: Vec<u32> = bmf.empty_glyphs().map(|ch| ch.id).collect();
+    empty.sort_unstable();
+
+    assert_eq!(empty, [0, 32]);
+}
+
+#[test]
+fn describe_glyph() {
+    let text = fs::read_to_string("assets/hiero.fnt").unwrap();
+    let bmf = BMFont::from_text(&text).unwrap();
+
+    let description = bmf.describe_glyph('A').unwrap();
+
+    assert_eq!(
+        description,
+        "U+0041 'A'\n\
+         rect: x=2 y=2 width=17 height=19\n\
+         offset: x=-1 y=6\n\
+         advance: 15\n\
+         page: 0 (hiero.png)\n\
+         channel: 0\n\
+         kerning: 'A' 'V' -1\n\
+         kerning: 'V' 'A' -1\n"
+    );
+    assert!(bmf.describe_glyph('Z').is_none());
+}
+
+#[test]
+fn format_conversions() {
+    let octets = fs::read("assets/menu.fnt").unwrap();
+
+    let text = bmf_parser::binary_to_text(&octets).unwrap();
+    assert_eq!(bmf_parser::text_to_binary(&text).unwrap(), octets);
+
+    let xml = bmf_parser::binary_to_xml(&octets).unwrap();
+    assert!(xml.starts_with("<?xml"));
+    assert_eq!(bmf_parser::xml_to_binary(&xml).unwrap(), octets);
+
+    let hiero = fs::read_to_string("assets/hiero.xml").unwrap();
+    let binary = bmf_parser::xml_to_binary(&hiero).unwrap();
+    check_hiero(&BMFont::from_xml(&bmf_parser::binary_to_xml(&binary).unwrap()).unwrap());
+
+    assert!(bmf_parser::binary_to_text(&octets[1..]).is_err());
+}
+
+#[test]
+fn xml_escapes_attribute_values() {
+    let mut bmf = BMFont::from_xml(&fs::read_to_string("assets/hiero.xml").unwrap()).unwrap();
+    bmf.info.as_mut().unwrap().font_name = "Sans \"<&>\"".to_string();
+
+    let reread = BMFont::from_xml(&bmf.to_xml()).unwrap();
+
+    assert_eq!(reread.face_name(), Some("Sans \"<&>\""));
+}
+
+#[test]
+fn xml_error_lines() {
+    let xml = "<?xml version=\"1.0\"?>\n<font>\n<!-- a\ncomment -->\n<info face=\"a\"\n size=\"12\"/>\n<bogus/>\n</font>\n";
+
+    let Err(bmf_parser::BmfError::Syntax { line, .. }) = BMFont::from_xml(xml) else {
+        panic!("expected a syntax error");
+    };
+
+    assert_eq!(line, 7);
+}
+
+#[test]
+fn quotes_in_names_stay_parseable() {
+    let mut bmf = BMFont::from_text(&fs::read_to_string("assets/hiero.fnt").unwrap()).unwrap();
+    bmf.info.as_mut().unwrap().font_name = "Open \"Sans\"".to_string();
+    bmf.pages[0] = "a\"b.png".to_string();
+
+    let reread = BMFont::from_text(&bmf.to_text()).unwrap();
+
+    assert_eq!(reread.face_name(), Some("Open 'Sans'"));
+    assert_eq!(reread.pages, ["a'b.png"]);
+}
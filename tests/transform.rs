@@ -0,0 +1,108 @@
+mod common;
+use common::menu_font;
+
+#[test]
+fn scale_round_trip() {
+    let bmf = menu_font();
+
+    let doubled = bmf.scaled(2.0);
+    let restored = doubled.scaled(0.5);
+
+    let common = doubled.common.as_ref().unwrap();
+    assert_eq!(
+        common.line_height,
+        2 * bmf.common.as_ref().unwrap().line_height
+    );
+    assert_eq!(restored.common, bmf.common);
+    assert_eq!(restored.info, bmf.info);
+    assert!(restored.diff(&bmf).is_empty());
+}
+
+#[test]
+fn rescale_keeps_base_within_line_height() {
+    let mut bmf = menu_font();
+    let common = bmf.common.as_mut().unwrap();
+    common.line_height = 5;
+    common.base = 5;
+
+    bmf.rescale_metrics(0.3);
+
+    let common = bmf.common.as_ref().unwrap();
+    assert_eq!(common.line_height, 2);
+    assert!(common.base <= common.line_height);
+}
+
+#[test]
+fn scaled_ratio_doubles_exactly() {
+    let bmf = menu_font();
+
+    let doubled = bmf.scaled_ratio(2, 1);
+
+    assert!(doubled.diff(&bmf.scaled(2.0)).is_empty());
+    assert_eq!(doubled.common, bmf.scaled(2.0).common);
+}
+
+#[test]
+fn scaled_ratio_three_halves_rounds_half_to_even() {
+    let mut bmf = menu_font();
+    let glyph = bmf.chars.get_mut(&('A' as u32)).unwrap();
+    glyph.width = 3;
+    glyph.height = 5;
+    glyph.x_offset = -3;
+
+    let scaled = bmf.scaled_ratio(3, 2);
+
+    let glyph = &scaled.chars[&('A' as u32)];
+    // 4.5 rounds to 4, 7.5 to 8, -4.5 to -4.
+    assert_eq!((glyph.width, glyph.height, glyph.x_offset), (4, 8, -4));
+}
+
+#[test]
+fn reflow_to_atlas() {
+    let mut bmf = menu_font();
+    let tallest = bmf.max_glyph_height();
+    let widest = bmf.max_glyph_width();
+
+    let Err(bmf_parser::PackError::GlyphTooLarge { id }) =
+        bmf.clone().reflow_to_atlas(widest - 1, 1024, 4)
+    else {
+        panic!("expected GlyphTooLarge");
+    };
+    assert_eq!(bmf.chars[&id].width, widest);
+    assert_eq!(
+        bmf.clone().reflow_to_atlas(widest, tallest, 1),
+        Err(bmf_parser::PackError::OutOfPages { max_pages: 1 })
+    );
+
+    bmf.reflow_to_atlas(widest * 2, tallest * 2, 255).unwrap();
+
+    let common = bmf.common.as_ref().unwrap();
+    assert!(common.pages > 1);
+    assert_eq!(bmf.pages.len(), common.pages as usize);
+    assert_eq!(bmf.pages[0], "test_0.png");
+    assert_eq!(bmf.pages[1], "test_0_1.png");
+    assert!(bmf.validate().is_empty());
+    assert!(bmf.overlapping_glyphs().is_empty());
+}
+
+#[test]
+fn reflow_to_atlas_edge_cases() {
+    let mut bmf = menu_font();
+    bmf.chars.retain(|id, _| *id == ' ' as u32);
+    assert_eq!(
+        bmf.clone().reflow_to_atlas(64, 64, 0),
+        Err(bmf_parser::PackError::OutOfPages { max_pages: 0 })
+    );
+
+    // Un-normalized names survive for the pages that already existed.
+    let mut bmf = menu_font();
+    bmf.raw_pages = vec!["C:\\art\\test_0.PNG".to_string()];
+    let tallest = bmf.max_glyph_height();
+    let widest = bmf.max_glyph_width();
+
+    bmf.reflow_to_atlas(widest * 2, tallest * 2, 255).unwrap();
+
+    assert_eq!(bmf.raw_pages.len(), bmf.pages.len());
+    assert_eq!(bmf.raw_pages[0], "C:\\art\\test_0.PNG");
+    assert_eq!(bmf.raw_pages[1], bmf.pages[1]);
+}
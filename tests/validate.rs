@@ -0,0 +1,167 @@
+use bmf_parser::BMFont;
+use std::fs;
+
+#[test]
+fn clamp_rects_to_atlas() {
+    let octets = fs::read("assets/menu.fnt").unwrap();
+    let mut bmf = BMFont::from_octets(&octets).unwrap();
+    let scale_w = bmf.common.as_ref().unwrap().scale_w;
+    let glyph = bmf.chars.get_mut(&('A' as u32)).unwrap();
+    glyph.x = scale_w - 2;
+    glyph.width = 10;
+
+    assert_eq!(bmf.rects_in_bounds(), vec!['A' as u32]);
+    assert_eq!(bmf.clamp_rects_to_atlas(), 1);
+    assert_eq!(bmf.chars[&('A' as u32)].width, 2);
+    assert!(bmf.validate().is_empty());
+    assert_eq!(bmf.clamp_rects_to_atlas(), 0);
+}
+
+#[test]
+fn atlas_occupancy() {
+    let octets = fs::read("assets/menu.fnt").unwrap();
+    let mut bmf = BMFont::from_octets(&octets).unwrap();
+    let occupancy = bmf.atlas_occupancy();
+    assert!(occupancy > 0.0 && occupancy < 1.0);
+
+    let common = bmf.common.as_ref().unwrap();
+    let (scale_w, scale_h) = (common.scale_w, common.scale_h);
+    bmf.chars.retain(|id, _| *id == 'A' as u32);
+    let glyph = bmf.chars.get_mut(&('A' as u32)).unwrap();
+    glyph.width = scale_w / 2;
+    glyph.height = scale_h;
+    bmf.pages.truncate(1);
+
+    assert_eq!(bmf.atlas_occupancy(), 0.5);
+}
+
+#[test]
+fn page_checks_name_their_authority() {
+    use bmf_parser::ValidationIssue;
+
+    let octets = fs::read("assets/menu.fnt").unwrap();
+    let mut bmf = BMFont::from_octets(&octets).unwrap();
+    bmf.pages.push("extra.png".to_string());
+    bmf.chars.get_mut(&('A' as u32)).unwrap().page = 1;
+    bmf.chars.get_mut(&('B' as u32)).unwrap().page = 2;
+
+    assert_eq!(
+        bmf.validate(),
+        [
+            ValidationIssue::PageBeyondDeclaredCount { id: 65, page: 1 },
+            ValidationIssue::PageBeyondDeclaredCount { id: 66, page: 2 },
+            ValidationIssue::PageBeyondPageList { id: 66, page: 2 },
+        ]
+    );
+}
+
+#[test]
+fn effective_pixel_size() {
+    let octets = fs::read("assets/menu.fnt").unwrap();
+    let mut bmf = BMFont::from_octets(&octets).unwrap();
+    let line_height = bmf.common.as_ref().unwrap().line_height;
+    assert_eq!(bmf.effective_pixel_size(), Some(96));
+
+    bmf.info.as_mut().unwrap().font_size = -80;
+    assert_eq!(bmf.effective_pixel_size(), Some(line_height));
+
+    bmf.common = None;
+    assert_eq!(bmf.effective_pixel_size(), Some(bmf.max_glyph_height()));
+
+    bmf.chars.clear();
+    assert_eq!(bmf.effective_pixel_size(), None);
+}
+
+#[test]
+fn verify_against_texture() {
+    let octets = fs::read("assets/menu.fnt").unwrap();
+    let bmf = BMFont::from_octets(&octets).unwrap();
+    let common = bmf.common.as_ref().unwrap();
+    let (scale_w, scale_h) = (common.scale_w as u32, common.scale_h as u32);
+    let a = &bmf['A'];
+
+    assert_eq!(bmf.verify_against_texture(0, scale_w, scale_h), Ok(()));
+    assert_eq!(bmf.verify_against_texture(1, 0, 0), Ok(()));
+    let narrow = bmf
+        .verify_against_texture(0, a.x as u32 + a.width as u32 - 1, scale_h)
+        .unwrap_err();
+    assert!(narrow.contains(&a.id));
+    assert!(narrow.windows(2).all(|w| w[0] < w[1]));
+}
+
+#[test]
+fn overlapping_glyphs() {
+    let octets = fs::read("assets/menu.fnt").unwrap();
+    let mut bmf = BMFont::from_octets(&octets).unwrap();
+    assert!(bmf.overlapping_glyphs().is_empty());
+
+    let a = bmf['A'].clone();
+    let glyph = bmf.chars.get_mut(&('B' as u32)).unwrap();
+    glyph.x = a.x + a.width - 1;
+    glyph.y = a.y + a.height - 1;
+    let glyph = bmf.chars.get_mut(&('C' as u32)).unwrap();
+    glyph.x = a.x + a.width;
+    glyph.y = a.y;
+    glyph.height = a.height - 1;
+
+    assert_eq!(bmf.overlapping_glyphs(), [(65, 66)]);
+}
+
+#[test]
+fn overlapping_glyphs_at_edge_of_range() {
+    let mut bmf = BMFont::from_ascii_grid("AB", 1000, 1000, 2);
+    let glyph = bmf.chars.get_mut(&('A' as u32)).unwrap();
+    (glyph.x, glyph.y) = (65000, 65000);
+    let glyph = bmf.chars.get_mut(&('B' as u32)).unwrap();
+    (glyph.x, glyph.y) = (65500, 65500);
+
+    assert_eq!(bmf.overlapping_glyphs(), [(65, 66)]);
+}
+
+#[test]
+fn implausible_offsets() {
+    use bmf_parser::ValidationIssue;
+
+    let octets = fs::read("assets/menu.fnt").unwrap();
+    let mut bmf = BMFont::from_octets(&octets).unwrap();
+    assert!(bmf.implausible_offsets().is_empty());
+
+    bmf.chars.get_mut(&('A' as u32)).unwrap().y_offset = -4000;
+    bmf.chars.get_mut(&('B' as u32)).unwrap().x_offset = 1000;
+
+    assert_eq!(bmf.implausible_offsets(), [65, 66]);
+    assert!(bmf
+        .validate()
+        .contains(&ValidationIssue::ImplausibleOffset { id: 65 }));
+}
+
+#[test]
+fn ink_area_per_page() {
+    let mut bmf = BMFont::from_ascii_grid("ABC", 4, 5, 3);
+    assert_eq!(bmf.ink_area_per_page(), [60]);
+
+    bmf.chars.get_mut(&('C' as u32)).unwrap().page = 2;
+    bmf.chars.get_mut(&('B' as u32)).unwrap().width = 0;
+    assert_eq!(bmf.ink_area_per_page(), [20, 0, 20]);
+}
+
+#[test]
+fn line_height_overflow() {
+    let octets = fs::read("assets/menu.fnt").unwrap();
+    let mut bmf = BMFont::from_octets(&octets).unwrap();
+    let lowest = bmf
+        .chars
+        .values()
+        .map(|ch| ch.y_offset as i32 + ch.height as i32)
+        .max()
+        .unwrap();
+
+    bmf.common.as_mut().unwrap().line_height = (lowest - 5) as u16;
+    assert_eq!(bmf.line_height_overflow(), Some(5));
+
+    bmf.common.as_mut().unwrap().line_height = lowest as u16;
+    assert_eq!(bmf.line_height_overflow(), None);
+
+    bmf.common = None;
+    assert_eq!(bmf.line_height_overflow(), None);
+}